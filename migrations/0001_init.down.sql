@@ -0,0 +1,20 @@
+DROP TABLE IF EXISTS honkai_traceMaterial;
+DROP TABLE IF EXISTS honkai_avatarEidolon;
+DROP TABLE IF EXISTS honkai_avatarTrace;
+DROP TABLE IF EXISTS honkai_avatarSkill;
+
+DROP TABLE IF EXISTS honkai_lightCone;
+DROP TABLE IF EXISTS honkai_lightConeSkill;
+DROP TABLE IF EXISTS honkai_eidolon;
+DROP TABLE IF EXISTS honkai_trace;
+DROP TABLE IF EXISTS honkai_skill;
+DROP TABLE IF EXISTS honkai_avatar;
+DROP TABLE IF EXISTS honkai_item;
+
+DROP TABLE IF EXISTS honkai_property;
+DROP TABLE IF EXISTS honkai_itemRarity;
+DROP TABLE IF EXISTS honkai_itemSubType;
+DROP TABLE IF EXISTS honkai_itemType;
+DROP TABLE IF EXISTS honkai_skillType;
+DROP TABLE IF EXISTS honkai_element;
+DROP TABLE IF EXISTS honkai_path;
@@ -0,0 +1,139 @@
+-- lookup tables, seeded from enums
+CREATE TABLE honkai_path (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_element (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_skillType (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_itemType (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_itemSubType (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_itemRarity (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_property (
+    name TEXT PRIMARY KEY NOT NULL,
+    type INTEGER NOT NULL
+);
+
+-- main tables, list columns hold JSON arrays
+CREATE TABLE honkai_item (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    rarity TEXT NOT NULL REFERENCES honkai_itemRarity (name),
+    item_type TEXT NOT NULL REFERENCES honkai_itemType (name),
+    item_sub_type TEXT NOT NULL REFERENCES honkai_itemSubType (name),
+    inventory_display_tag INTEGER NOT NULL,
+    purpose_type INTEGER,
+    desc TEXT NOT NULL,
+    bgdesc TEXT NOT NULL,
+    pile_limit INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_avatar (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    rarity INTEGER NOT NULL,
+    votag TEXT NOT NULL,
+    element TEXT NOT NULL REFERENCES honkai_element (name),
+    path TEXT NOT NULL REFERENCES honkai_path (name),
+    spneed INTEGER NOT NULL
+);
+
+CREATE TABLE honkai_skill (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    type_desc TEXT NOT NULL,
+    max_level INTEGER NOT NULL,
+    spbase REAL,
+    spneed REAL,
+    attack_type TEXT REFERENCES honkai_skillType (name),
+    skill_desc TEXT NOT NULL,
+    param_list TEXT NOT NULL
+);
+
+CREATE TABLE honkai_trace (
+    id INTEGER PRIMARY KEY NOT NULL,
+    max_level INTEGER NOT NULL,
+    point_type INTEGER NOT NULL,
+    anchor TEXT NOT NULL,
+    default_unlock INTEGER NOT NULL,
+    avatar_promotion_limit TEXT NOT NULL,
+    pre_point TEXT NOT NULL,
+    point_desc TEXT NOT NULL,
+    param_list TEXT NOT NULL
+);
+
+CREATE TABLE honkai_eidolon (
+    id INTEGER PRIMARY KEY NOT NULL,
+    rank INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    desc TEXT NOT NULL,
+    unlock_cost TEXT NOT NULL,
+    param TEXT NOT NULL
+);
+
+CREATE TABLE honkai_lightConeSkill (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    desc TEXT NOT NULL,
+    param_list TEXT NOT NULL,
+    ability_property TEXT NOT NULL
+);
+
+CREATE TABLE honkai_lightCone (
+    id INTEGER PRIMARY KEY NOT NULL,
+    release INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    rarity INTEGER NOT NULL,
+    path TEXT NOT NULL REFERENCES honkai_path (name),
+    max_promotion INTEGER NOT NULL,
+    max_rank INTEGER NOT NULL,
+    skill_id INTEGER NOT NULL REFERENCES honkai_lightConeSkill (id)
+);
+
+-- relations
+CREATE TABLE honkai_avatarSkill (
+    avatar_id INTEGER NOT NULL REFERENCES honkai_avatar (id) ON DELETE CASCADE,
+    skill_id INTEGER NOT NULL REFERENCES honkai_skill (id) ON DELETE CASCADE,
+    PRIMARY KEY (avatar_id, skill_id)
+);
+
+CREATE TABLE honkai_avatarTrace (
+    avatar_id INTEGER NOT NULL REFERENCES honkai_avatar (id) ON DELETE CASCADE,
+    point_id INTEGER NOT NULL REFERENCES honkai_trace (id) ON DELETE CASCADE,
+    PRIMARY KEY (avatar_id, point_id)
+);
+
+CREATE TABLE honkai_avatarEidolon (
+    avatar_id INTEGER NOT NULL REFERENCES honkai_avatar (id) ON DELETE CASCADE,
+    eidolon_id INTEGER NOT NULL REFERENCES honkai_eidolon (id) ON DELETE CASCADE,
+    PRIMARY KEY (avatar_id, eidolon_id)
+);
+
+CREATE TABLE honkai_traceMaterial (
+    item_id INTEGER NOT NULL REFERENCES honkai_item (id),
+    point_id INTEGER NOT NULL REFERENCES honkai_trace (id) ON DELETE CASCADE,
+    level INTEGER NOT NULL,
+    item_num INTEGER NOT NULL,
+    PRIMARY KEY (item_id, point_id, level)
+);
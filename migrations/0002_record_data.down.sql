@@ -0,0 +1,7 @@
+ALTER TABLE honkai_avatar DROP COLUMN data;
+ALTER TABLE honkai_skill DROP COLUMN data;
+ALTER TABLE honkai_trace DROP COLUMN data;
+ALTER TABLE honkai_eidolon DROP COLUMN data;
+ALTER TABLE honkai_lightConeSkill DROP COLUMN data;
+ALTER TABLE honkai_lightCone DROP COLUMN data;
+ALTER TABLE honkai_item DROP COLUMN data;
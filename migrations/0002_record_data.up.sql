@@ -0,0 +1,8 @@
+-- whole serialized record, served by the sql data backend
+ALTER TABLE honkai_avatar ADD COLUMN data TEXT;
+ALTER TABLE honkai_skill ADD COLUMN data TEXT;
+ALTER TABLE honkai_trace ADD COLUMN data TEXT;
+ALTER TABLE honkai_eidolon ADD COLUMN data TEXT;
+ALTER TABLE honkai_lightConeSkill ADD COLUMN data TEXT;
+ALTER TABLE honkai_lightCone ADD COLUMN data TEXT;
+ALTER TABLE honkai_item ADD COLUMN data TEXT;
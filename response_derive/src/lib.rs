@@ -39,7 +39,7 @@ pub fn response_derive_macro(input: TokenStream) -> TokenStream {
                                         .status(StatusCode::OK)
                                         .header("Content-Type", "application/json")
                                         .body(serde_json::to_string(val)?.into())?),
-                    Err(e) => Err(e.to_string().into()),
+                    Err(e) => Ok(e.into()),
                 }
             }
         }
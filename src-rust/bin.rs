@@ -2,11 +2,8 @@ mod builder;
 mod handler;
 mod routes;
 
-use crate::routes::{
-    app_router,
-    cron::write_db,
-    cron::{dm_file_splitting, dm_repo_clone},
-};
+use crate::routes::{app_router, cron::dm_repo_clone, cron::write_db};
+use builder::config::env_config;
 use handler::error::WorkerError;
 use std::{net::SocketAddr, time::Duration};
 use tokio_cron_scheduler::{Job, JobScheduler};
@@ -24,40 +21,42 @@ async fn main() -> Result<(), WorkerError> {
         .with_ansi(ANSI)
         .init();
 
+    let config = env_config();
     let sched = JobScheduler::new().await?;
     sched
         .add(Job::new_repeated_async(
-            Duration::from_secs(1800), //half hour
+            Duration::from_secs(config.cron.refresh_interval),
             |_uuid, _l| {
                 Box::pin(async move {
                     let _ = dm_repo_clone::execute().await;
-                    let _ = dm_file_splitting::execute().await;
                 })
             },
         )?)
         .await?;
 
-    sched
-        .add(Job::new_repeated_async(
-            Duration::from_secs(3600 * 6), // every 6 hours
-            |_uuid, _l| {
-                Box::pin(async move {
-                    let _ = write_db::execute().await;
-                })
-            },
-        )?)
-        .await?;
+    // the job only fetches from `remote`, which offline mode never does
+    if !config.data.offline {
+        sched
+            .add(Job::new_repeated_async(
+                Duration::from_secs(config.cron.write_db_interval),
+                |_uuid, _l| {
+                    Box::pin(async move {
+                        let _ = write_db::execute().await;
+                    })
+                },
+            )?)
+            .await?;
+    }
 
     tracing::info!("cronjob starting...");
     sched.start().await?;
 
     tokio::spawn(async move {
         let _ = dm_repo_clone::execute().await;
-        let _ = dm_file_splitting::execute().await;
     });
     // db_populate::execute().await?;
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 5005));
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
     tracing::info!("listening on {}", addr);
     axum::Server::bind(&addr)
         .serve(app_router().into_make_service())
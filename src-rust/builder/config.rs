@@ -1,10 +1,44 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::io::Error as IoError;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::{fs, path::PathBuf};
+use strum_macros::EnumString;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[cfg(target_os = "windows")]
+const DEFAULT_LOCAL_TMP: &str = "c:\\tmp\\";
+#[cfg(target_os = "linux")]
+const DEFAULT_LOCAL_TMP: &str = "/tmp/";
+
+const DEFAULT_LOCAL_REPO: &str = "../StarRailData";
+const DEFAULT_REMOTE: &str = "https://raw.githubusercontent.com/Dimbreath/StarRailData/master/";
+const DEFAULT_REPO_URL: &str = "https://github.com/Dimbreath/StarRailData.git";
+const DEFAULT_SNAPSHOT_ROOT: &str = "../StarRailData.snapshots";
+const DEFAULT_KEEP_SNAPSHOTS: usize = 3;
+const DEFAULT_PORT: u16 = 5005;
+/// half hour
+const DEFAULT_REFRESH_INTERVAL: u64 = 1800;
+/// every 6 hours
+const DEFAULT_WRITE_DB_INTERVAL: u64 = 3600 * 6;
+
+static ENV_CONFIG: OnceLock<Arc<EnvConfig>> = OnceLock::new();
+
+/// config loaded once from `config.toml` and the environment
+pub fn env_config() -> Arc<EnvConfig> {
+    // tests never read the local config or the network
+    #[cfg(test)]
+    let init = EnvConfig::fixture;
+    #[cfg(not(test))]
+    let init = EnvConfig::new;
+    ENV_CONFIG.get_or_init(|| Arc::new(init())).clone()
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct ConfigToml {
     database: Option<ConfigTomlDatabase>,
+    data: Option<ConfigTomlData>,
+    server: Option<ConfigTomlServer>,
+    cron: Option<ConfigTomlCron>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -13,10 +47,81 @@ struct ConfigTomlDatabase {
     auth_token: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ConfigTomlData {
+    local_tmp: Option<PathBuf>,
+    local_repo: Option<PathBuf>,
+    remote: Option<String>,
+    repo_url: Option<String>,
+    snapshot_root: Option<PathBuf>,
+    keep_snapshots: Option<usize>,
+    offline: Option<bool>,
+    backend: Option<DataBackend>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ConfigTomlServer {
+    port: Option<u16>,
+    alias_extend: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ConfigTomlCron {
+    refresh_interval: Option<u64>,
+    write_db_interval: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
 pub struct EnvConfig {
     pub db_url: String,
     pub db_auth_token: String,
+    pub data: DataConfig,
+    pub server: ServerConfig,
+    pub cron: CronConfig,
+}
+
+/// where datamine files are read from and written to
+#[derive(Debug, Clone)]
+pub struct DataConfig {
+    /// cache for files fetched from `remote`
+    pub local_tmp: PathBuf,
+    /// checked out DM repo, used until a snapshot is promoted
+    pub local_repo: PathBuf,
+    /// raw file url the DM repo is served from
+    pub remote: String,
+    /// git url snapshots are fetched from
+    pub repo_url: String,
+    pub snapshot_root: PathBuf,
+    pub keep_snapshots: usize,
+    /// never fetch from `remote` or `repo_url`, missing files are errors
+    pub offline: bool,
+    /// where the honkai handlers read records from
+    pub backend: DataBackend,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum DataBackend {
+    /// the dataset files
+    #[default]
+    Json,
+    /// the seeded database in `db_url`
+    Sql,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub port: u16,
+    /// serve `POST /honkai/alias`, which adds aliases until the next restart
+    pub alias_extend: bool,
+}
+
+/// job intervals in seconds
+#[derive(Debug, Clone)]
+pub struct CronConfig {
+    pub refresh_interval: u64,
+    pub write_db_interval: u64,
 }
 
 impl Default for EnvConfig {
@@ -30,7 +135,8 @@ impl EnvConfig {
         let filepaths: [&str; 2] = ["./config.toml", "./Config.toml"];
         let mut content: String = "".to_owned();
 
-        for filepath in filepaths {
+        let custom_path = std::env::var("NAS_CONFIG").ok();
+        for filepath in custom_path.iter().map(String::as_str).chain(filepaths) {
             let result: Result<String, IoError> = fs::read_to_string(filepath);
 
             if let Ok(inner) = result {
@@ -41,9 +147,34 @@ impl EnvConfig {
 
         let config_toml: ConfigToml = toml::from_str(&content).unwrap_or_else(|_| {
             println!("Failed to create ConfigToml Object out of config file.");
-            ConfigToml { database: None }
+            ConfigToml::default()
         });
 
+        Self::from_toml(config_toml)
+    }
+
+    /// offline config over the dataset bundled in `fixtures/`
+    #[cfg(test)]
+    pub fn fixture() -> Self {
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/StarRailData");
+        let mut config = Self::from_toml(ConfigToml::default());
+        config.data = DataConfig {
+            // a miss must not be served from a previous fetch in the real tmp
+            local_tmp: fixture.clone(),
+            local_repo: fixture,
+            remote: DEFAULT_REMOTE.into(),
+            repo_url: DEFAULT_REPO_URL.into(),
+            snapshot_root: std::env::temp_dir().join("nas-ws-fixture/snapshots"),
+            keep_snapshots: DEFAULT_KEEP_SNAPSHOTS,
+            offline: true,
+            backend: DataBackend::Json,
+        };
+        config
+    }
+
+    /// builds the config from a parsed toml file, environment variables take
+    /// precedence over file values
+    fn from_toml(config_toml: ConfigToml) -> Self {
         let (url, auth_token): (String, String) = match config_toml.database {
             Some(database) => {
                 let db_url: String = database.url.unwrap_or_else(|| {
@@ -64,9 +195,87 @@ impl EnvConfig {
             }
         };
 
+        let data = config_toml.data.unwrap_or_default();
+        let server = config_toml.server.unwrap_or_default();
+        let cron = config_toml.cron.unwrap_or_default();
+
         EnvConfig {
-            db_url: url,
-            db_auth_token: auth_token,
+            db_url: env_or("NAS_DB_URL", Some(url)).unwrap_or_default(),
+            db_auth_token: env_or("NAS_DB_AUTH_TOKEN", Some(auth_token)).unwrap_or_default(),
+            data: DataConfig {
+                local_tmp: env_or("NAS_DATA_LOCAL_TMP", data.local_tmp)
+                    .unwrap_or_else(|| DEFAULT_LOCAL_TMP.into()),
+                local_repo: env_or("NAS_DATA_LOCAL_REPO", data.local_repo)
+                    .unwrap_or_else(|| DEFAULT_LOCAL_REPO.into()),
+                remote: env_or("NAS_DATA_REMOTE", data.remote)
+                    .unwrap_or_else(|| DEFAULT_REMOTE.into()),
+                repo_url: env_or("NAS_DATA_REPO_URL", data.repo_url)
+                    .unwrap_or_else(|| DEFAULT_REPO_URL.into()),
+                snapshot_root: env_or("NAS_DATA_SNAPSHOT_ROOT", data.snapshot_root)
+                    .unwrap_or_else(|| DEFAULT_SNAPSHOT_ROOT.into()),
+                keep_snapshots: env_or("NAS_DATA_KEEP_SNAPSHOTS", data.keep_snapshots)
+                    .unwrap_or(DEFAULT_KEEP_SNAPSHOTS),
+                offline: env_or("NAS_DATA_OFFLINE", data.offline).unwrap_or_default(),
+                backend: env_or("NAS_DATA_BACKEND", data.backend).unwrap_or_default(),
+            },
+            server: ServerConfig {
+                port: env_or("NAS_SERVER_PORT", server.port).unwrap_or(DEFAULT_PORT),
+                alias_extend: env_or("NAS_SERVER_ALIAS_EXTEND", server.alias_extend)
+                    .unwrap_or_default(),
+            },
+            cron: CronConfig {
+                refresh_interval: env_or("NAS_CRON_REFRESH_INTERVAL", cron.refresh_interval)
+                    .unwrap_or(DEFAULT_REFRESH_INTERVAL),
+                write_db_interval: env_or("NAS_CRON_WRITE_DB_INTERVAL", cron.write_db_interval)
+                    .unwrap_or(DEFAULT_WRITE_DB_INTERVAL),
+            },
+        }
+    }
+}
+
+/// reads `key` from the environment, falling back to `fallback` when the
+/// variable is unset or can't be parsed
+fn env_or<T: FromStr>(key: &str, fallback: Option<T>) -> Option<T> {
+    match std::env::var(key).ok().map(|value| value.parse::<T>()) {
+        Some(Ok(value)) => Some(value),
+        Some(Err(_)) => {
+            println!("Failed to parse environment variable `{key}`, ignoring.");
+            fallback
         }
+        None => fallback,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigToml, DataBackend, EnvConfig, DEFAULT_PORT};
+    use std::path::PathBuf;
+
+    #[test]
+    fn toml_values() {
+        let config_toml: ConfigToml = toml::from_str(
+            r#"
+            [database]
+            url = "libsql://localhost"
+            auth_token = "token"
+
+            [data]
+            local_repo = "/data/StarRailData"
+            keep_snapshots = 5
+            backend = "sql"
+
+            [cron]
+            refresh_interval = 60
+            "#,
+        )
+        .unwrap();
+        let config = EnvConfig::from_toml(config_toml);
+        assert_eq!(config.data.local_repo, PathBuf::from("/data/StarRailData"));
+        assert_eq!(config.data.keep_snapshots, 5);
+        assert_eq!(config.data.backend, DataBackend::Sql);
+        assert_eq!(config.cron.refresh_interval, 60);
+        // unset tables fall back to defaults
+        assert_eq!(config.server.port, DEFAULT_PORT);
+        assert!(!config.server.alias_extend);
     }
 }
@@ -0,0 +1,198 @@
+use crate::handler::error::WorkerError;
+use libsql_client::{args, Client, Statement, Value};
+use tracing::info;
+
+/// a versioned schema change, `up` and `down` are plain SQL scripts under
+/// `migrations/`
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+/// every migration in version order, versions start at 1 and have no gaps
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        up: include_str!("../../migrations/0001_init.up.sql"),
+        down: include_str!("../../migrations/0001_init.down.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "record_data",
+        up: include_str!("../../migrations/0002_record_data.up.sql"),
+        down: include_str!("../../migrations/0002_record_data.down.sql"),
+    },
+];
+
+/// schema version the seeders are written against
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+const CREATE_MIGRATION_TABLE: &str = "CREATE TABLE IF NOT EXISTS _migrations (
+    version INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+)";
+
+/// highest applied migration, 0 for an empty database
+pub async fn current_version(client: &Client) -> Result<u32, WorkerError> {
+    client.execute(CREATE_MIGRATION_TABLE).await?;
+    let result = client
+        .execute("SELECT MAX(version) FROM _migrations")
+        .await?;
+    let version = match result.rows.first().and_then(|row| row.values.first()) {
+        Some(Value::Integer { value }) => *value as u32,
+        _ => 0,
+    };
+    Ok(version)
+}
+
+/// applies every pending migration up to `target` (latest if `None`), each
+/// migration runs in its own transaction
+pub async fn migrate_up(client: &Client, target: Option<u32>) -> Result<u32, WorkerError> {
+    let target = target.unwrap_or(SCHEMA_VERSION);
+    if target > SCHEMA_VERSION {
+        return Err(WorkerError::ParseData(format!(
+            "migration {target} does not exist, latest is {SCHEMA_VERSION}"
+        )));
+    }
+    let current = current_version(client).await?;
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current && migration.version <= target)
+    {
+        info!(
+            "applying migration {:04}_{}",
+            migration.version, migration.name
+        );
+        let mut statements: Vec<Statement> = split_statements(migration.up)
+            .into_iter()
+            .map(Statement::new)
+            .collect();
+        statements.push(Statement::with_args(
+            "INSERT INTO _migrations (version, name) VALUES (?, ?)",
+            args!(migration.version, migration.name),
+        ));
+        client.batch(statements).await?;
+    }
+    current_version(client).await
+}
+
+/// reverts applied migrations down to `target` (0 drops every table if
+/// `None`), newest first
+pub async fn migrate_down(client: &Client, target: Option<u32>) -> Result<u32, WorkerError> {
+    let target = target.unwrap_or(0);
+    let current = current_version(client).await?;
+    for migration in MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|migration| migration.version <= current && migration.version > target)
+    {
+        info!(
+            "reverting migration {:04}_{}",
+            migration.version, migration.name
+        );
+        let mut statements: Vec<Statement> = split_statements(migration.down)
+            .into_iter()
+            .map(Statement::new)
+            .collect();
+        statements.push(Statement::with_args(
+            "DELETE FROM _migrations WHERE version = ?",
+            args!(migration.version),
+        ));
+        client.batch(statements).await?;
+    }
+    current_version(client).await
+}
+
+/// errors unless the database is exactly at `SCHEMA_VERSION`, seeding an
+/// outdated schema would fail halfway through with a less useful error
+pub async fn ensure_schema(client: &Client) -> Result<(), WorkerError> {
+    let found = current_version(client).await?;
+    match found == SCHEMA_VERSION {
+        true => Ok(()),
+        false => Err(WorkerError::SchemaMismatch {
+            expected: SCHEMA_VERSION,
+            found,
+        }),
+    }
+}
+
+/// splits a script into single statements, libsql only executes one
+/// statement per request. `--` comments are dropped, statements must not
+/// contain `;` inside string literals
+fn split_statements(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or_default())
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .split(';')
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        current_version, ensure_schema, migrate_down, migrate_up, split_statements, MIGRATIONS,
+        SCHEMA_VERSION,
+    };
+    use crate::handler::error::WorkerError;
+    use libsql_client::{Client, Config};
+
+    async fn local_client(name: &str) -> Client {
+        let path = std::env::temp_dir().join(format!("nas-ws-{name}.sqlite"));
+        let _ = std::fs::remove_file(&path);
+        Client::from_config(Config {
+            url: url::Url::from_file_path(&path).unwrap(),
+            auth_token: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn split_script() {
+        let statements = split_statements(
+            "-- header\nCREATE TABLE a (id INTEGER); -- trailing\n\nDROP TABLE b;\n",
+        );
+        assert_eq!(
+            statements,
+            vec!["CREATE TABLE a (id INTEGER)", "DROP TABLE b"]
+        );
+    }
+
+    #[test]
+    fn versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn up_and_down() {
+        let client = local_client("migrate").await;
+        assert_eq!(current_version(&client).await.unwrap(), 0);
+        assert!(matches!(
+            ensure_schema(&client).await,
+            Err(WorkerError::SchemaMismatch { found: 0, .. })
+        ));
+
+        assert_eq!(migrate_up(&client, None).await.unwrap(), SCHEMA_VERSION);
+        ensure_schema(&client).await.unwrap();
+        // applying again is a no-op
+        assert_eq!(migrate_up(&client, None).await.unwrap(), SCHEMA_VERSION);
+        client
+            .execute("INSERT INTO honkai_path (name, type) VALUES ('Erudition', 4)")
+            .await
+            .unwrap();
+
+        assert_eq!(migrate_down(&client, None).await.unwrap(), 0);
+        assert!(client.execute("SELECT * FROM honkai_path").await.is_err());
+    }
+}
@@ -3,11 +3,13 @@ use async_trait::async_trait;
 use libsql_client::{Client, Config};
 use serde::Deserialize;
 
-use self::config::EnvConfig;
+use self::config::env_config;
 
 pub mod config;
+pub mod migration;
 pub mod traits;
 mod types;
+pub mod typescript;
 
 #[derive(Deserialize)]
 pub struct MyParams {
@@ -25,14 +27,38 @@ pub trait AsyncInto<T>: Sized + Send + Sync {
     fn into_using_resource(self, resource: &Self::Resource) -> Result<T, WorkerError>;
 }
 
+/// client of the database in the config
 pub async fn get_db_client() -> Result<Client, WorkerError> {
-    let env = EnvConfig::new();
+    let env = env_config();
+    connect_db(url::Url::parse(&env.db_url)?).await
+}
 
-    let client = Client::from_config(Config {
-        url: url::Url::parse(&env.db_url)?,
-        auth_token: Some(env.db_auth_token),
-    })
-    .await?;
+/// client of a database given as `sqlite:<path>` or `libsql:<url>`, remote
+/// databases use the auth token in the config
+pub async fn get_db_client_for(target: &str) -> Result<Client, WorkerError> {
+    let url = match target.split_once(':') {
+        Some(("sqlite", path)) => {
+            let path = std::env::current_dir()?.join(path);
+            url::Url::from_file_path(&path).map_err(|_| {
+                WorkerError::ParseData(format!("bad sqlite path {}", path.display()))
+            })?
+        }
+        Some(("libsql", url)) if url.contains("://") => url::Url::parse(url)?,
+        Some(("libsql", host)) => url::Url::parse(&format!("libsql://{host}"))?,
+        _ => {
+            return Err(WorkerError::ParseData(format!(
+                "unknown database {target}, expected sqlite:<path> or libsql:<url>"
+            )))
+        }
+    };
+    connect_db(url).await
+}
 
+async fn connect_db(url: url::Url) -> Result<Client, WorkerError> {
+    let auth_token = match url.scheme() {
+        "file" => None,
+        _ => Some(env_config().db_auth_token.clone()),
+    };
+    let client = Client::from_config(Config { url, auth_token }).await?;
     Ok(client)
 }
@@ -1,8 +1,21 @@
 use crate::handler::error::WorkerError;
 use async_trait::async_trait;
+use libsql_client::Statement;
 
 #[async_trait]
 pub trait DbAction {
-    async fn seed() -> Result<(), WorkerError>;
-    async fn teardown() -> Result<(), WorkerError>;
+    /// tables written by `seed_statements`, parent tables first
+    fn tables() -> &'static [&'static str];
+
+    /// upserts every row of the dataset
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError>;
+
+    /// clears every table in `tables`, child tables first
+    fn teardown_statements() -> Vec<Statement> {
+        Self::tables()
+            .iter()
+            .rev()
+            .map(|table| Statement::new(format!("DELETE FROM {table}")))
+            .collect()
+    }
 }
@@ -0,0 +1,321 @@
+use crate::routes::{
+    honkai::{
+        banner::types::Banner,
+        combat_log::types::MvpAnalysis,
+        dm_api::{
+            atlas::SignatureAtlas,
+            character::{
+                eidolon::AvatarRankConfig, promotion_config::AvatarPromotionConfig,
+                types::AvatarConfig,
+            },
+            character_skill::types::{AvatarSkillConfig, AvatarSkillTreeConfig, LeveledSkill},
+            equipment::{
+                equipment_config::EquipmentConfig,
+                equipment_promotion_config::EquipmentPromotionConfig,
+                equipment_skill_config::EquipmentSkillConfig, stat_ranking::EquipmentRanking,
+            },
+            equipment_skill::skill_tree_config::SkillTreeConfig,
+            property::config::AvatarPropertyConfig,
+            relic::{
+                config::RelicConfig, main_affix::RelicMainAffixConfig,
+                set_config::RelicSetConfig, set_skill_config::RelicSetSkillConfig,
+                sub_affix::RelicSubAffixConfig,
+            },
+        },
+        jade_estimate::types::EstimateCfg,
+        patch::types::{Patch, PatchBanner},
+        simulator::types::Log,
+    },
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+
+const HEADER: &str = "// This file is generated by the `codegen` binary, do not edit by hand\n\n";
+
+/// Collects the json schema of every registered type and renders them as
+/// typescript declarations
+///
+/// Every type is rendered under its schema name so enums like `Element` or
+/// `Path` keep their names on the TS side.
+pub struct TsGenerator {
+    gen: SchemaGenerator,
+}
+
+impl Default for TsGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TsGenerator {
+    pub fn new() -> Self {
+        let gen = SchemaSettings::draft07()
+            .with(|settings| {
+                settings.option_nullable = false;
+                settings.option_add_null_type = true;
+            })
+            .into_generator();
+        Self { gen }
+    }
+
+    /// registers a type, types it depends on are registered along the way
+    pub fn register<T: JsonSchema>(&mut self) -> &mut Self {
+        self.gen.subschema_for::<T>();
+        self
+    }
+
+    /// renders every registered type, sorted by name
+    pub fn render(&self) -> String {
+        let mut out = String::from(HEADER);
+        for (name, schema) in self.gen.definitions() {
+            out.push_str(&declaration(name, schema));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// typescript declarations of every type served by the API
+pub fn api_typescript() -> String {
+    TsGenerator::new()
+        .register::<PatchBanner>()
+        .register::<Patch>()
+        .register::<Banner>()
+        .register::<EstimateCfg>()
+        .register::<SkillTreeConfig>()
+        .register::<SignatureAtlas>()
+        .register::<AvatarConfig>()
+        .register::<AvatarPromotionConfig>()
+        .register::<AvatarSkillConfig>()
+        .register::<LeveledSkill>()
+        .register::<AvatarSkillTreeConfig>()
+        .register::<AvatarPropertyConfig>()
+        .register::<AvatarRankConfig>()
+        .register::<EquipmentConfig>()
+        .register::<EquipmentPromotionConfig>()
+        .register::<EquipmentSkillConfig>()
+        .register::<EquipmentRanking>()
+        .register::<RelicConfig>()
+        .register::<RelicSetConfig>()
+        .register::<RelicSetSkillConfig>()
+        .register::<RelicSubAffixConfig>()
+        .register::<RelicMainAffixConfig>()
+        .register::<MvpAnalysis>()
+        .register::<Log>()
+        .render()
+}
+
+fn declaration(name: &str, schema: &Schema) -> String {
+    let name = type_name(name);
+    let doc = match schema {
+        Schema::Object(SchemaObject {
+            metadata: Some(metadata),
+            ..
+        }) => match &metadata.description {
+            Some(description) => format!("/** {} */\n", description.replace('\n', " ")),
+            None => String::new(),
+        },
+        _ => String::new(),
+    };
+
+    match schema {
+        Schema::Object(obj) if is_plain_object(obj) => {
+            format!("{doc}export interface {name} {}\n", object_body(obj, true))
+        }
+        _ => format!("{doc}export type {name} = {};\n", ts_type(schema)),
+    }
+}
+
+/// an object with known properties and no unions, rendered as an interface
+fn is_plain_object(obj: &SchemaObject) -> bool {
+    let is_object = matches!(
+        &obj.instance_type,
+        Some(SingleOrVec::Single(ttype)) if **ttype == InstanceType::Object
+    );
+    is_object
+        && obj.subschemas.is_none()
+        && obj
+            .object
+            .as_ref()
+            .is_some_and(|object| !object.properties.is_empty())
+}
+
+fn ts_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".into(),
+        Schema::Bool(false) => "never".into(),
+        Schema::Object(obj) => object_type(obj),
+    }
+}
+
+fn object_type(obj: &SchemaObject) -> String {
+    if let Some(reference) = &obj.reference {
+        return type_name(reference.rsplit('/').next().unwrap_or(reference));
+    }
+    if let Some(value) = &obj.const_value {
+        return value.to_string();
+    }
+    if let Some(values) = &obj.enum_values {
+        return union(values.iter().map(|value| value.to_string()).collect());
+    }
+    if let Some(subschemas) = &obj.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            return all_of
+                .iter()
+                .map(|schema| wrap(ts_type(schema)))
+                .collect::<Vec<String>>()
+                .join(" & ");
+        }
+        if let Some(variants) = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref()) {
+            return union(variants.iter().map(ts_type).collect());
+        }
+    }
+    match &obj.instance_type {
+        Some(SingleOrVec::Single(ttype)) => instance_type(ttype, obj),
+        Some(SingleOrVec::Vec(ttypes)) => {
+            union(ttypes.iter().map(|ttype| instance_type(ttype, obj)).collect())
+        }
+        None if obj.object.is_some() => object_body(obj, false),
+        None => "unknown".into(),
+    }
+}
+
+fn instance_type(ttype: &InstanceType, obj: &SchemaObject) -> String {
+    match ttype {
+        InstanceType::Null => "null".into(),
+        InstanceType::Boolean => "boolean".into(),
+        InstanceType::Integer | InstanceType::Number => "number".into(),
+        InstanceType::String => "string".into(),
+        InstanceType::Array => match obj.array.as_ref().and_then(|array| array.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => format!("{}[]", wrap(ts_type(item))),
+            Some(SingleOrVec::Vec(items)) => format!(
+                "[{}]",
+                items.iter().map(ts_type).collect::<Vec<String>>().join(", ")
+            ),
+            None => "unknown[]".into(),
+        },
+        InstanceType::Object => object_body(obj, false),
+    }
+}
+
+/// renders the properties of an object, maps without known properties are
+/// rendered as `Record`
+fn object_body(obj: &SchemaObject, multiline: bool) -> String {
+    let Some(object) = &obj.object else {
+        return "Record<string, unknown>".into();
+    };
+    if object.properties.is_empty() {
+        let value = match object.additional_properties.as_deref() {
+            Some(schema) => ts_type(schema),
+            None => "unknown".into(),
+        };
+        return format!("Record<string, {value}>");
+    }
+
+    let fields: Vec<String> = object
+        .properties
+        .iter()
+        .map(|(key, schema)| {
+            let optional = match object.required.contains(key) {
+                true => "",
+                false => "?",
+            };
+            format!("{}{optional}: {}", property_name(key), ts_type(schema))
+        })
+        .collect();
+
+    match multiline {
+        true => format!("{{\n  {};\n}}", fields.join(";\n  ")),
+        false => format!("{{ {} }}", fields.join("; ")),
+    }
+}
+
+/// members are sorted so the output doesn't depend on map ordering
+fn union(mut members: Vec<String>) -> String {
+    members.sort();
+    members.dedup();
+    match members.is_empty() {
+        true => "never".into(),
+        false => members.join(" | "),
+    }
+}
+
+/// wraps unions and intersections in parens so they can be used as an
+/// array item or inside an intersection
+fn wrap(ts: String) -> String {
+    match ts.contains(" | ") || ts.contains(" & ") {
+        true => format!("({ts})"),
+        false => ts,
+    }
+}
+
+/// schemars names generic types like `List_for_AvatarConfig`, anything that
+/// isn't a valid identifier character gets replaced
+fn type_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+fn property_name(key: &str) -> String {
+    let is_ident = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match is_ident {
+        true => key.to_owned(),
+        false => format!("{key:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{api_typescript, union};
+
+    #[test]
+    fn deterministic() {
+        let members = ["\"b\"", "null", "\"a\"", "null", "\"b\""];
+        assert_eq!(
+            union(members.map(String::from).to_vec()),
+            "\"a\" | \"b\" | null"
+        );
+        assert_eq!(union(Vec::new()), "never");
+
+        // every top level union comes out sorted and without duplicates
+        let ts = api_typescript();
+        for line in ts.lines().filter(|line| line.starts_with("export type ")) {
+            let Some((_, members)) = line.trim_end_matches(';').split_once(" = ") else {
+                continue;
+            };
+            if members.contains(['{', '(', '&']) {
+                continue;
+            }
+            let members: Vec<&str> = members.split(" | ").collect();
+            let mut sorted = members.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(members, sorted, "{line}");
+        }
+    }
+
+    #[test]
+    fn enum_names() {
+        let ts = api_typescript();
+        for name in ["Element", "Path", "SkillType", "RelicType"] {
+            assert!(
+                ts.contains(&format!("export type {name} = ")),
+                "missing enum {name}"
+            );
+        }
+        assert!(ts.contains("export interface AvatarConfig {"));
+    }
+}
@@ -1,61 +1,194 @@
-use libsql_client::Statement;
-use nas_ws::builder::get_db_client;
+use clap::{Parser, Subcommand};
+use libsql_client::{Client, Statement};
+use nas_ws::builder::migration::{
+    current_version, ensure_schema, migrate_down, migrate_up, MIGRATIONS, SCHEMA_VERSION,
+};
+use nas_ws::builder::{get_db_client, get_db_client_for};
 use nas_ws::handler::error::WorkerError;
-use nas_ws::runnables::db::{seed_common, seed_table};
-
-// TODO: teardown
-async fn teardown() -> Result<(), WorkerError> {
-    let client = get_db_client().await?;
-    let table_list = vec![
-        // "frameworks",
-        // "blogs",
-
-        "honkai_avatarSkill",
-        "honkai_avatarTrace",
-        "honkai_avatarPromotion",
-        "honkai_avatarEidolon",
-        "honkai_lightConeSkill",
-        "honkai_traceMaterial",
-
-        "honkai_lightCone",
-        "honkai_eidolon",
-        "honkai_item",
-        "honkai_skill",
-
-        "honkai_skillType",
-
-        "honkai_itemRarity",
-        "honkai_itemSubType",
-        "honkai_itemType",
-
-        "honkai_property",
-
-        "honkai_trace",
-        "honkai_avatar",
-
-        "honkai_element",
-        "honkai_path",
-    ];
-
-    client
-        .batch(
-            table_list
-                .into_iter()
-                .map(|name| {
-                    let st = format!("DROP TABLE IF EXISTS {}", name);
-                    Statement::new(st)
-                })
-                .collect::<Vec<Statement>>(),
-        )
-        .await?;
-    Ok(())
+use nas_ws::runnables::db::{plan, row_counts, DbTable, SeedReport};
+use strum::IntoEnumIterator;
+
+/// manages the database configured by `NAS_DB_URL`/`config.toml`
+#[derive(Debug, Parser)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+    /// target another database, `sqlite:<path>` or `libsql:<url>`
+    #[arg(long, global = true)]
+    db: Option<String>,
+    /// print the SQL of `seed`, `teardown` and `reset` instead of running it,
+    /// `seed` still reads the database to diff against
+    #[arg(long, global = true)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// apply or revert schema migrations
+    Migrate {
+        #[command(subcommand)]
+        direction: Direction,
+    },
+    /// write the rows that differ from the dataset, the schema has to be
+    /// migrated to the latest version
+    Seed {
+        /// only seed these entries (e.g. `AvatarConfig`), every entry if empty
+        #[arg(long)]
+        table: Vec<DbTable>,
+    },
+    /// delete every row seeded by the given entries
+    Teardown {
+        /// only clear these entries, every entry if empty
+        #[arg(long)]
+        table: Vec<DbTable>,
+    },
+    /// revert every migration, migrate to the latest version and seed
+    Reset,
+    /// schema version and row count of every table
+    Status,
+    /// compare the rows in the dataset with the rows in the database
+    Diff {
+        /// only compare these entries, every entry if empty
+        #[arg(long)]
+        table: Vec<DbTable>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum Direction {
+    /// apply pending migrations
+    Up {
+        /// stop at this version instead of the latest
+        #[arg(long)]
+        to: Option<u32>,
+    },
+    /// revert applied migrations
+    Down {
+        /// stop at this version instead of reverting everything
+        #[arg(long)]
+        to: Option<u32>,
+    },
+    /// print the applied and latest schema version
+    Status,
+}
+
+/// selected entries in seeding order, every entry if none were given
+fn selected(tables: Vec<DbTable>) -> Vec<DbTable> {
+    DbTable::iter()
+        .filter(|table| tables.is_empty() || tables.contains(table))
+        .collect()
+}
+
+fn print_statements(statements: &[Statement]) {
+    statements
+        .iter()
+        .for_each(|statement| println!("{statement};"));
+}
+
+fn print_reports(reports: &[SeedReport]) {
+    println!(
+        "{:<24} {:>8} {:>8} {:>8} {:>9}",
+        "table", "inserted", "updated", "deleted", "unchanged"
+    );
+    for report in reports {
+        println!(
+            "{:<24} {:>8} {:>8} {:>8} {:>9}",
+            report.table, report.inserted, report.updated, report.deleted, report.unchanged
+        );
+    }
+}
+
+async fn seed_statements(tables: &[DbTable]) -> Result<Vec<Statement>, WorkerError> {
+    let mut statements = Vec::new();
+    for table in tables {
+        statements.extend(table.seed_statements().await?);
+    }
+    Ok(statements)
+}
+
+async fn client(args: &Args) -> Result<Client, WorkerError> {
+    match &args.db {
+        Some(target) => get_db_client_for(target).await,
+        None => get_db_client().await,
+    }
 }
 
-// TODO: clap
 #[tokio::main]
 pub async fn main() -> Result<(), WorkerError> {
-    // teardown().await?;
-    seed_common().await?;
-    seed_table().await?;
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let args = Args::parse();
+    match &args.command {
+        Command::Migrate { direction } => {
+            let client = client(&args).await?;
+            let version = match direction {
+                Direction::Up { to } => migrate_up(&client, *to).await?,
+                Direction::Down { to } => migrate_down(&client, *to).await?,
+                Direction::Status => current_version(&client).await?,
+            };
+            println!("schema version {version}, latest {SCHEMA_VERSION}");
+        }
+        Command::Seed { table } => {
+            let client = client(&args).await?;
+            let seed_plan = plan(&client, &selected(table.clone())).await?;
+            if args.dry_run {
+                seed_plan
+                    .statements()
+                    .for_each(|statement| println!("{statement};"));
+                return Ok(());
+            }
+            seed_plan.apply(&client).await?;
+            print_reports(&seed_plan.reports);
+        }
+        Command::Teardown { table } => {
+            // child tables are cleared before the tables they reference
+            let tables: Vec<DbTable> = selected(table.clone()).into_iter().rev().collect();
+            if args.dry_run {
+                tables
+                    .iter()
+                    .for_each(|table| print_statements(&table.teardown_statements()));
+                return Ok(());
+            }
+            let client = client(&args).await?;
+            for table in tables {
+                table.teardown(&client).await?;
+            }
+        }
+        Command::Reset => {
+            let tables = selected(Vec::new());
+            if args.dry_run {
+                MIGRATIONS
+                    .iter()
+                    .rev()
+                    .for_each(|migration| println!("{}", migration.down));
+                MIGRATIONS
+                    .iter()
+                    .for_each(|migration| println!("{}", migration.up));
+                print_statements(&seed_statements(&tables).await?);
+                return Ok(());
+            }
+            let client = client(&args).await?;
+            migrate_down(&client, None).await?;
+            migrate_up(&client, None).await?;
+            let seed_plan = plan(&client, &tables).await?;
+            seed_plan.apply(&client).await?;
+            print_reports(&seed_plan.reports);
+        }
+        Command::Status => {
+            let client = client(&args).await?;
+            let version = current_version(&client).await?;
+            println!("schema version {version}, latest {SCHEMA_VERSION}");
+            ensure_schema(&client).await?;
+            for (table, count) in row_counts(&client).await? {
+                println!("{table:<24} {count:>8}");
+            }
+        }
+        Command::Diff { table } => {
+            let client = client(&args).await?;
+            print_reports(&plan(&client, &selected(table.clone())).await?.reports);
+        }
+    }
     Ok(())
 }
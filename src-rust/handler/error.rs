@@ -1,8 +1,12 @@
+use super::request_id::current_request_id;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
+use schemars::JsonSchema;
 use serde::Serialize;
+use serde_json::{json, Value};
 use std::fmt::Display;
 
 /// general error type of the application, this should be used in junctions
@@ -18,25 +22,133 @@ pub enum WorkerError {
     EmptyBody,
     ServerSide,
     NotFound(String),
+    /// a dataset file is missing locally and offline mode forbids fetching it
+    OfflineCacheMiss(String),
+    /// a record of the upstream dataset can't be converted, not the fault of
+    /// the client
+    UpstreamData(String),
+    /// the database schema is not at the version the seeders expect
+    SchemaMismatch {
+        expected: u32,
+        found: u32,
+    },
     Unknown(anyhow::Error),
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, JsonSchema)]
 pub enum ComputationType {
     BadDateComparison,
     BadNumberCast,
 }
 
+/// machine-readable error code, shared between every transport
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    ParseData,
+    Computation,
+    WrongMethod,
+    EmptyBody,
+    ServerSide,
+    NotFound,
+    OfflineCacheMiss,
+    UpstreamData,
+    SchemaMismatch,
+    Unknown,
+}
+
+/// JSON body of every failed REST response
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+pub struct ErrorEnvelope {
+    pub code: ErrorCode,
+    pub message: String,
+    /// extra context of the error, shape depends on `code`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    /// id of the failed request, also printed in the server log
+    pub request_id: String,
+}
+
 impl WorkerError {
     pub fn code(&self) -> StatusCode {
         match self {
             WorkerError::ParseData(_) => StatusCode::BAD_REQUEST,
             WorkerError::EmptyBody => StatusCode::BAD_REQUEST,
-            WorkerError::NotFound(_) => StatusCode::BAD_REQUEST,
+            WorkerError::NotFound(_) => StatusCode::NOT_FOUND,
             WorkerError::WrongMethod => StatusCode::METHOD_NOT_ALLOWED,
+            WorkerError::OfflineCacheMiss(_) => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            WorkerError::ParseData(_) => ErrorCode::ParseData,
+            WorkerError::Computation(_) => ErrorCode::Computation,
+            WorkerError::WrongMethod => ErrorCode::WrongMethod,
+            WorkerError::EmptyBody => ErrorCode::EmptyBody,
+            WorkerError::ServerSide => ErrorCode::ServerSide,
+            WorkerError::NotFound(_) => ErrorCode::NotFound,
+            WorkerError::OfflineCacheMiss(_) => ErrorCode::OfflineCacheMiss,
+            WorkerError::UpstreamData(_) => ErrorCode::UpstreamData,
+            WorkerError::SchemaMismatch { .. } => ErrorCode::SchemaMismatch,
+            WorkerError::Unknown(_) => ErrorCode::Unknown,
+        }
+    }
+
+    pub fn grpc_code(&self) -> tonic::Code {
+        match self {
+            WorkerError::NotFound(_) => tonic::Code::NotFound,
+            WorkerError::ParseData(_) | WorkerError::EmptyBody => tonic::Code::InvalidArgument,
+            WorkerError::Computation(_) | WorkerError::SchemaMismatch { .. } => {
+                tonic::Code::FailedPrecondition
+            }
+            WorkerError::WrongMethod => tonic::Code::Unimplemented,
+            WorkerError::OfflineCacheMiss(_) => tonic::Code::Unavailable,
+            WorkerError::ServerSide | WorkerError::UpstreamData(_) | WorkerError::Unknown(_) => {
+                tonic::Code::Internal
+            }
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            WorkerError::ParseData(reason) => Some(json!({ "reason": reason })),
+            WorkerError::Computation(comp_type) => Some(json!({ "type": comp_type })),
+            WorkerError::NotFound(resource) => Some(json!({ "resource": resource })),
+            WorkerError::OfflineCacheMiss(path) => Some(json!({ "path": path })),
+            WorkerError::UpstreamData(reason) => Some(json!({ "reason": reason })),
+            WorkerError::SchemaMismatch { expected, found } => {
+                Some(json!({ "expected": expected, "found": found }))
+            }
+            // the chain stays in the server log
+            _ => None,
+        }
+    }
+
+    /// builds the error envelope under the id of the current request and
+    /// logs the error with that id so the response can be traced back
+    pub fn envelope(&self) -> ErrorEnvelope {
+        let request_id = current_request_id();
+        match self {
+            WorkerError::Unknown(inner) => {
+                tracing::error!("[{request_id}] stacktrace: {}", inner.backtrace());
+                inner.chain().for_each(|er| {
+                    tracing::error!("[{request_id}] chain: {}", er);
+                });
+            }
+            _ => tracing::error!("[{request_id}] {}", self),
+        }
+        ErrorEnvelope {
+            code: self.error_code(),
+            message: match self {
+                WorkerError::Unknown(_) => WorkerError::ServerSide.to_string(),
+                _ => self.to_string(),
+            },
+            details: self.details(),
+            request_id,
+        }
+    }
 }
 
 impl Display for WorkerError {
@@ -50,7 +162,14 @@ impl Display for WorkerError {
                 // ComputationType::BadNumberCast => todo!(),
                 _ => "Computation error from the server".to_owned(),
             },
-            Self::NotFound(resource) => format!("Resrouce/ID {resource} not found"),
+            Self::NotFound(resource) => format!("Resource/ID {resource} not found"),
+            Self::OfflineCacheMiss(path) => {
+                format!("{path} is not in the local dataset and offline mode is enabled")
+            }
+            Self::UpstreamData(reason) => format!("Bad upstream data\nReason: {reason}"),
+            Self::SchemaMismatch { expected, found } => format!(
+                "Database schema is at version {found}, expected {expected}. Run `db-dev migrate up` first"
+            ),
             Self::WrongMethod => "Method is not supported".to_owned(),
             Self::EmptyBody => "Missing body data".to_owned(),
             Self::Unknown(err) => format!("Unknown error: {}", err),
@@ -62,39 +181,44 @@ impl Display for WorkerError {
 
 impl IntoResponse for WorkerError {
     fn into_response(self) -> Response {
-        let code = self.code();
-        let msg = self.to_string();
-        if let WorkerError::Unknown(inner) = self {
-            tracing::error!("stacktrace: {}", &inner.backtrace());
-            inner.chain().for_each(|er| {
-                tracing::error!("chain: {}", er);
-            });
-        } else {
-            tracing::error!(msg);
-        };
-        (code, msg).into_response()
+        (self.code(), Json(self.envelope())).into_response()
     }
 }
 
-impl From<WorkerError> for vercel_runtime::Response<vercel_runtime::Body> {
-    fn from(value: WorkerError) -> Self {
-        // is this a safe unwrap ?
+impl From<&WorkerError> for vercel_runtime::Response<vercel_runtime::Body> {
+    fn from(value: &WorkerError) -> Self {
+        // envelope only contains strings and json values, serializing can't fail
+        let body = serde_json::to_string(&value.envelope()).unwrap_or_default();
+        // static status and header, this is a safe unwrap
         vercel_runtime::Response::builder()
             .status(value.code())
-            .body(value.to_string().into())
+            .header("Content-Type", "application/json")
+            .body(body.into())
             .unwrap()
     }
 }
 
+impl From<WorkerError> for vercel_runtime::Response<vercel_runtime::Body> {
+    fn from(value: WorkerError) -> Self {
+        Self::from(&value)
+    }
+}
+
 impl From<WorkerError> for vercel_runtime::Error {
     fn from(value: WorkerError) -> Self {
-        vercel_runtime::Error::from(value.to_string())
+        let body = serde_json::to_string(&value.envelope()).unwrap_or_default();
+        vercel_runtime::Error::from(body)
     }
 }
 
 impl From<WorkerError> for tonic::Status {
     fn from(value: WorkerError) -> Self {
-        Self::internal(value.to_string())
+        let envelope = value.envelope();
+        let mut status = Self::new(value.grpc_code(), envelope.message);
+        if let Ok(request_id) = envelope.request_id.parse() {
+            status.metadata_mut().insert("x-request-id", request_id);
+        }
+        status
     }
 }
 
@@ -108,3 +232,81 @@ where
         Self::Unknown(err.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ComputationType, ErrorCode, WorkerError};
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    #[test]
+    fn not_found_status() {
+        let err = WorkerError::NotFound("123".into());
+        assert_eq!(err.code(), StatusCode::NOT_FOUND);
+        assert_eq!(err.to_string(), "Resource/ID 123 not found");
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn envelope_shape() {
+        let envelope = WorkerError::NotFound("123".into()).envelope();
+        let value = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(value["code"], "NOT_FOUND");
+        assert_eq!(value["details"]["resource"], "123");
+        assert_eq!(value["request_id"].as_str().unwrap().len(), 16);
+        assert!(WorkerError::EmptyBody.envelope().details.is_none());
+
+        let unknown = WorkerError::Unknown(anyhow::anyhow!("db password is hunter2"));
+        let envelope = unknown.envelope();
+        assert_eq!(envelope.message, "Unknown server error");
+        assert!(envelope.details.is_none());
+        assert_eq!(
+            WorkerError::ParseData("bad".into()).error_code(),
+            ErrorCode::ParseData
+        );
+    }
+
+    #[test]
+    fn grpc_status() {
+        let cases = [
+            (WorkerError::NotFound("1".into()), tonic::Code::NotFound),
+            (
+                WorkerError::ParseData("x".into()),
+                tonic::Code::InvalidArgument,
+            ),
+            (WorkerError::EmptyBody, tonic::Code::InvalidArgument),
+            (
+                WorkerError::Computation(ComputationType::BadDateComparison),
+                tonic::Code::FailedPrecondition,
+            ),
+            (WorkerError::ServerSide, tonic::Code::Internal),
+            (
+                WorkerError::UpstreamData("skill 1 has no level 1 entry".into()),
+                tonic::Code::Internal,
+            ),
+            (
+                WorkerError::OfflineCacheMiss("TextMap/TextMapEN.json".into()),
+                tonic::Code::Unavailable,
+            ),
+            (
+                WorkerError::SchemaMismatch {
+                    expected: 2,
+                    found: 1,
+                },
+                tonic::Code::FailedPrecondition,
+            ),
+        ];
+        for (err, code) in cases {
+            let status: tonic::Status = err.into();
+            assert_eq!(status.code(), code);
+            assert!(status.metadata().get("x-request-id").is_some());
+        }
+    }
+
+    #[test]
+    fn vercel_response() {
+        let res: vercel_runtime::Response<vercel_runtime::Body> =
+            WorkerError::NotFound("1".into()).into();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers()["Content-Type"], "application/json");
+    }
+}
@@ -1,4 +1,5 @@
 pub mod error;
+pub mod request_id;
 
 pub trait FromAxumResponse<Inner, FromError, ToError: Send + Sync> {
     type TFrom;
@@ -0,0 +1,75 @@
+use axum::{
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Span;
+
+/// header the request id is read from and echoed back in
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    /// id of the request being handled
+    static REQUEST_ID: String;
+}
+
+fn new_request_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+/// id of the request being handled, a fresh one outside of a request
+pub fn current_request_id() -> String {
+    REQUEST_ID
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| new_request_id())
+}
+
+/// reuses the `x-request-id` of the client or makes one up, the request is
+/// handled under that id and it is echoed in the response
+pub async fn request_id<B>(mut req: Request<B>, next: Next<B>) -> Response {
+    let value = match req.headers().get(REQUEST_ID_HEADER) {
+        Some(value) if !value.is_empty() && value.to_str().is_ok() => value.clone(),
+        // hex digits are always a valid header value
+        _ => HeaderValue::from_str(&new_request_id()).unwrap(),
+    };
+    let id = value.to_str().unwrap_or_default().to_owned();
+    // the trace span reads it from the request
+    req.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+
+    let mut res = REQUEST_ID.scope(id, next.run(req)).await;
+    res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    res
+}
+
+/// span of `TraceLayer` with the request id in it
+pub fn make_span<B>(req: &Request<B>) -> Span {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        uri = %req.uri(),
+        request_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_request_id, REQUEST_ID};
+    use crate::handler::error::WorkerError;
+
+    #[tokio::test]
+    async fn scoped_id() {
+        let envelope = REQUEST_ID
+            .scope("abc123".into(), async {
+                assert_eq!(current_request_id(), "abc123");
+                WorkerError::ServerSide.envelope()
+            })
+            .await;
+        assert_eq!(envelope.request_id, "abc123");
+        assert_ne!(current_request_id(), current_request_id());
+    }
+}
@@ -1,32 +1,47 @@
-use crate::handler::error::WorkerError;
-use std::path::Path;
-use tokio::process::Command;
-use tracing::{error, info};
+use super::{dm_file_splitting, snapshot::SnapshotStore, validate::validate_dataset};
+use crate::{
+    builder::config::env_config,
+    handler::error::WorkerError,
+    routes::honkai::traits::{set_dataset_root, DATASET_ROOT_OVERRIDE},
+};
+use tracing::info;
 
+/// fetches the newest DM commit into a fresh snapshot and switches readers
+/// over once it passes validation, the previous snapshot stays active
+/// otherwise. Split files are written into the snapshot before it's promoted
 pub async fn execute() -> Result<(), WorkerError> {
-    info!("spinning up DM repo...");
-    if Path::new("../StarRailData").exists() {
-        info!("local DM directory exists, attempting pull...");
-        let _pull = Command::new("git")
-            .args(["-C", "../StarRailData", "pull"])
-            .output()
-            .await?;
-        info!("pull completed");
-        return Ok(());
+    info!("refreshing DM dataset...");
+    if env_config().data.offline {
+        info!("offline mode, keeping the local DM dataset");
+        return dm_file_splitting::execute().await;
     }
-    info!("local DM directory does not exist, attempting clone...");
-    let _clone = Command::new("git")
-        .args([
-            "clone",
-            "https://github.com/Dimbreath/StarRailData.git",
-            "../StarRailData",
-        ])
-        .output()
-        .await
-        .map_err(|_| {
-            error!("cloning failed");
-            WorkerError::ServerSide
-        });
-    info!("DM repo cloned");
+    let store = SnapshotStore::default();
+    restore(&store);
+
+    let snapshot = store
+        .refresh(|staging| {
+            DATASET_ROOT_OVERRIDE.scope(staging, async {
+                let report = validate_dataset().await;
+                if !report.valid {
+                    return Err(WorkerError::ParseData(format!(
+                        "DM dataset failed validation with {} errors",
+                        report.errors().count()
+                            + report.parsed.iter().filter(|check| !check.success).count()
+                    )));
+                }
+                dm_file_splitting::execute().await
+            })
+        })
+        .await?;
+    set_dataset_root(snapshot);
+    info!("DM dataset refreshed");
     Ok(())
 }
+
+/// points readers at the snapshot promoted by a previous run, so a restart
+/// serves the same data even if the next refresh fails
+fn restore(store: &SnapshotStore) {
+    if let Some(active) = store.active_dir() {
+        set_dataset_root(active);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod dm_file_splitting;
 pub mod dm_repo_clone;
+pub mod snapshot;
+pub mod status;
+pub mod validate;
 pub mod write_db;
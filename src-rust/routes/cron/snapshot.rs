@@ -0,0 +1,447 @@
+use crate::{builder::config::env_config, handler::error::WorkerError};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+use tracing::{error, info};
+
+const MIRROR_DIR: &str = "mirror.git";
+const ACTIVE_FILE: &str = "ACTIVE";
+const HISTORY_FILE: &str = "history.json";
+const STAGING_SUFFIX: &str = ".tmp";
+/// refresh records kept in `history.json`
+const HISTORY_LIMIT: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RefreshRecord {
+    /// upstream commit the refresh resolved to, `None` if fetching failed
+    pub commit: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Immutable dataset snapshots, one directory per upstream commit
+///
+/// layout of `root`:
+/// - `mirror.git`: bare mirror of the upstream repo
+/// - `<seq>-<commit>`: checked out dataset, never modified once promoted.
+///   `seq` counts up with every new snapshot, so ids sort oldest first
+/// - `<seq>-<commit>.tmp`: snapshot being checked out or validated
+/// - `ACTIVE`: id of the promoted snapshot
+/// - `history.json`: latest refresh records, newest first
+pub struct SnapshotStore {
+    root: PathBuf,
+    remote: String,
+    keep: usize,
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        let config = env_config();
+        Self::new(
+            config.data.snapshot_root.clone(),
+            config.data.repo_url.clone(),
+            config.data.keep_snapshots,
+        )
+    }
+}
+
+impl SnapshotStore {
+    pub fn new(root: impl Into<PathBuf>, remote: impl Into<String>, keep: usize) -> Self {
+        Self {
+            root: root.into(),
+            remote: remote.into(),
+            // the active snapshot is always kept
+            keep: keep.max(1),
+        }
+    }
+
+    pub fn snapshot_dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+
+    /// id of the promoted snapshot
+    pub fn active_snapshot(&self) -> Option<String> {
+        std::fs::read_to_string(self.root.join(ACTIVE_FILE))
+            .ok()
+            .map(|id| id.trim().to_owned())
+            .filter(|id| !id.is_empty())
+    }
+
+    pub fn active_commit(&self) -> Option<String> {
+        self.active_snapshot()
+            .map(|id| snapshot_commit(&id).to_owned())
+    }
+
+    /// directory of the promoted snapshot, if it still exists on disk
+    pub fn active_dir(&self) -> Option<PathBuf> {
+        self.active_snapshot()
+            .map(|id| self.snapshot_dir(&id))
+            .filter(|dir| dir.is_dir())
+    }
+
+    pub fn history(&self) -> Vec<RefreshRecord> {
+        std::fs::read_to_string(self.root.join(HISTORY_FILE))
+            .ok()
+            .and_then(|history| serde_json::from_str(&history).ok())
+            .unwrap_or_default()
+    }
+
+    /// ids of every promoted snapshot, newest first
+    pub fn snapshots(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return vec![];
+        };
+        let mut snapshots: Vec<(u64, String)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.ends_with(STAGING_SUFFIX) {
+                    return None;
+                }
+                Some((snapshot_seq(&name)?, name))
+            })
+            .collect();
+        snapshots.sort_by(|a, b| b.cmp(a));
+        snapshots.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// id of the promoted snapshot of `commit`, if it is still retained
+    pub fn find(&self, commit: &str) -> Option<String> {
+        self.snapshots()
+            .into_iter()
+            .find(|id| snapshot_commit(id) == commit)
+    }
+
+    /// Fetches upstream and promotes its newest commit
+    ///
+    /// The commit is checked out into a staging directory and `validate` is
+    /// called with that directory. Only once it passes is the directory
+    /// renamed to its final name and `ACTIVE` pointed at it, a failing
+    /// validation leaves the active snapshot untouched.
+    ///
+    /// Returns the directory of the promoted snapshot, switching readers over
+    /// is up to the caller.
+    pub async fn refresh<F, Fut>(&self, validate: F) -> Result<PathBuf, WorkerError>
+    where
+        F: FnOnce(PathBuf) -> Fut,
+        Fut: Future<Output = Result<(), WorkerError>>,
+    {
+        let started_at = Utc::now();
+        let mut commit = None;
+        let result = self.try_refresh(&mut commit, validate).await;
+
+        let message = match &result {
+            Ok(_) => "promoted".to_owned(),
+            Err(err) => {
+                error!("dataset refresh failed: {err}");
+                err.to_string()
+            }
+        };
+        self.push_history(RefreshRecord {
+            commit,
+            started_at,
+            finished_at: Utc::now(),
+            success: result.is_ok(),
+            message,
+        })?;
+        result
+    }
+
+    async fn try_refresh<F, Fut>(
+        &self,
+        commit_out: &mut Option<String>,
+        validate: F,
+    ) -> Result<PathBuf, WorkerError>
+    where
+        F: FnOnce(PathBuf) -> Fut,
+        Fut: Future<Output = Result<(), WorkerError>>,
+    {
+        std::fs::create_dir_all(&self.root)?;
+        self.sync_mirror().await?;
+
+        let mirror = self.mirror_dir();
+        let commit = git(&["--git-dir", path_str(&mirror), "rev-parse", "HEAD"]).await?;
+        *commit_out = Some(commit.clone());
+
+        // promoted snapshots already passed validation
+        let id = match self.find(&commit) {
+            Some(id) => id,
+            None => {
+                let seq = self
+                    .snapshots()
+                    .first()
+                    .and_then(|newest| snapshot_seq(newest))
+                    .unwrap_or_default();
+                format!("{:06}-{commit}", seq + 1)
+            }
+        };
+        let target = self.snapshot_dir(&id);
+        if !target.is_dir() {
+            info!("materialising snapshot {id}");
+            let staging = self.root.join(format!("{id}{STAGING_SUFFIX}"));
+            if staging.exists() {
+                std::fs::remove_dir_all(&staging)?;
+            }
+            std::fs::create_dir_all(&staging)?;
+
+            let checkout = git(&[
+                "--git-dir",
+                path_str(&mirror),
+                "--work-tree",
+                path_str(&staging),
+                "checkout",
+                &commit,
+                "--",
+                ".",
+            ])
+            .await;
+            let staged = match checkout {
+                Ok(_) => validate(staging.clone()).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = staged {
+                std::fs::remove_dir_all(&staging)?;
+                return Err(err);
+            }
+            std::fs::rename(&staging, &target)?;
+        }
+
+        self.write_active(&id)?;
+        self.prune(&id)?;
+        info!("snapshot {id} is active");
+        Ok(target)
+    }
+
+    fn mirror_dir(&self) -> PathBuf {
+        self.root.join(MIRROR_DIR)
+    }
+
+    async fn sync_mirror(&self) -> Result<(), WorkerError> {
+        let mirror = self.mirror_dir();
+        match mirror.exists() {
+            true => {
+                git(&[
+                    "--git-dir",
+                    path_str(&mirror),
+                    "fetch",
+                    "--prune",
+                    &self.remote,
+                    "+refs/heads/*:refs/heads/*",
+                ])
+                .await?
+            }
+            false => git(&["clone", "--bare", &self.remote, path_str(&mirror)]).await?,
+        };
+        Ok(())
+    }
+
+    /// replaces the pointer file in one rename so it's never half written
+    fn write_active(&self, id: &str) -> Result<(), WorkerError> {
+        let staging = self.root.join(format!("{ACTIVE_FILE}{STAGING_SUFFIX}"));
+        std::fs::write(&staging, id)?;
+        std::fs::rename(staging, self.root.join(ACTIVE_FILE))?;
+        Ok(())
+    }
+
+    fn push_history(&self, record: RefreshRecord) -> Result<(), WorkerError> {
+        std::fs::create_dir_all(&self.root)?;
+        let mut history = self.history();
+        history.insert(0, record);
+        history.truncate(HISTORY_LIMIT);
+        std::fs::write(
+            self.root.join(HISTORY_FILE),
+            serde_json::to_string_pretty(&history)?,
+        )?;
+        Ok(())
+    }
+
+    /// removes all but the newest `keep` snapshots, never the active one
+    fn prune(&self, active: &str) -> Result<(), WorkerError> {
+        let stale = self
+            .snapshots()
+            .into_iter()
+            .filter(|id| id != active)
+            .skip(self.keep - 1);
+        for id in stale {
+            info!("pruning snapshot {id}");
+            std::fs::remove_dir_all(self.snapshot_dir(&id))?;
+        }
+        Ok(())
+    }
+}
+
+/// sequence number of a snapshot id, `None` for anything else in `root`
+fn snapshot_seq(id: &str) -> Option<u64> {
+    let (seq, _) = id.split_once('-')?;
+    seq.parse().ok()
+}
+
+/// commit hash a snapshot id was checked out from
+pub fn snapshot_commit(id: &str) -> &str {
+    id.split_once('-').map_or(id, |(_, commit)| commit)
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap_or_default()
+}
+
+/// runs a git command, returning its trimmed stdout
+async fn git(args: &[&str]) -> Result<String, WorkerError> {
+    let output = Command::new("git").args(args).output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        error!("git {} failed: {}", args.join(" "), stderr);
+        return Err(WorkerError::Unknown(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{git, path_str, snapshot_commit, SnapshotStore};
+    use crate::handler::error::WorkerError;
+    use std::path::{Path, PathBuf};
+
+    /// scratch directory with a bare upstream repo and a working clone to
+    /// push commits from
+    struct Upstream {
+        dir: PathBuf,
+    }
+
+    impl Upstream {
+        async fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "nas-ws-snapshot-{name}-{:08x}",
+                rand::random::<u32>()
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            let bare = dir.join("upstream.git");
+            let work = dir.join("work");
+            git(&["init", "--bare", path_str(&bare)]).await.unwrap();
+            git(&["clone", path_str(&bare), path_str(&work)])
+                .await
+                .unwrap();
+            Self { dir }
+        }
+
+        fn remote(&self) -> String {
+            self.dir.join("upstream.git").to_string_lossy().to_string()
+        }
+
+        fn store(&self, keep: usize) -> SnapshotStore {
+            SnapshotStore::new(self.dir.join("snapshots"), self.remote(), keep)
+        }
+
+        /// commits `ExcelOutput/Data.json` with the given content and pushes
+        /// it, returning the commit hash
+        async fn push(&self, content: &str) -> String {
+            let work = self.dir.join("work");
+            let work = path_str(&work);
+            std::fs::create_dir_all(Path::new(work).join("ExcelOutput")).unwrap();
+            std::fs::write(Path::new(work).join("ExcelOutput/Data.json"), content).unwrap();
+            git(&["-C", work, "add", "-A"]).await.unwrap();
+            git(&[
+                "-C",
+                work,
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@localhost",
+                "commit",
+                "-m",
+                content,
+            ])
+            .await
+            .unwrap();
+            git(&["-C", work, "push", "origin", "HEAD"]).await.unwrap();
+            git(&["-C", work, "rev-parse", "HEAD"]).await.unwrap()
+        }
+    }
+
+    impl Drop for Upstream {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_promotes_snapshot() {
+        let upstream = Upstream::new("promote").await;
+        let store = upstream.store(2);
+        let commit = upstream.push("first").await;
+
+        let dir = store.refresh(|_| async { Ok(()) }).await.unwrap();
+        let id = store.find(&commit).unwrap();
+        assert_eq!(dir, store.snapshot_dir(&id));
+        assert_eq!(store.active_snapshot(), Some(id));
+        assert_eq!(store.active_commit(), Some(commit));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("ExcelOutput/Data.json")).unwrap(),
+            "first"
+        );
+        assert!(store.history()[0].success);
+    }
+
+    #[tokio::test]
+    async fn failed_validation_keeps_active() {
+        let upstream = Upstream::new("rollback").await;
+        let store = upstream.store(2);
+        let good = upstream.push("good").await;
+        store.refresh(|_| async { Ok(()) }).await.unwrap();
+
+        let bad = upstream.push("bad").await;
+        let refreshed = store
+            .refresh(|dir| async move {
+                let data = std::fs::read_to_string(dir.join("ExcelOutput/Data.json"))?;
+                match data.as_str() {
+                    "bad" => Err(WorkerError::ParseData(data)),
+                    _ => Ok(()),
+                }
+            })
+            .await;
+        assert!(refreshed.is_err());
+        assert_eq!(store.active_commit(), Some(good.clone()));
+        assert!(store.find(&bad).is_none());
+        let snapshots = store.snapshots();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshot_commit(&snapshots[0]), good);
+
+        let history = store.history();
+        assert_eq!(history.len(), 2);
+        assert!(!history[0].success);
+        assert_eq!(history[0].commit, Some(bad));
+    }
+
+    #[tokio::test]
+    async fn prune_old_snapshots() {
+        let upstream = Upstream::new("prune").await;
+        let store = upstream.store(2);
+        let mut commits = vec![];
+        for content in ["one", "two", "three"] {
+            commits.push(upstream.push(content).await);
+            store.refresh(|_| async { Ok(()) }).await.unwrap();
+        }
+
+        // ids order by promotion, not by directory mtimes
+        let snapshots: Vec<String> = store
+            .snapshots()
+            .iter()
+            .map(|id| snapshot_commit(id).to_owned())
+            .collect();
+        assert_eq!(snapshots, vec![commits[2].clone(), commits[1].clone()]);
+        assert!(store.find(&commits[0]).is_none());
+        assert_eq!(store.active_commit().as_ref(), Some(&commits[2]));
+    }
+}
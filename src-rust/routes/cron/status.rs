@@ -0,0 +1,26 @@
+use super::snapshot::{RefreshRecord, SnapshotStore};
+use crate::{handler::error::WorkerError, routes::honkai::traits::dataset_root};
+use axum::Json;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct DatasetStatus {
+    pub active_commit: Option<String>,
+    /// directory readers currently resolve data files against
+    pub dataset_root: String,
+    /// retained snapshots, newest first
+    pub snapshots: Vec<String>,
+    /// latest refreshes, newest first
+    pub history: Vec<RefreshRecord>,
+}
+
+pub async fn execute() -> Result<Json<DatasetStatus>, WorkerError> {
+    let store = SnapshotStore::default();
+    Ok(Json(DatasetStatus {
+        active_commit: store.active_commit(),
+        dataset_root: dataset_root().to_string_lossy().to_string(),
+        snapshots: store.snapshots(),
+        history: store.history(),
+    }))
+}
@@ -0,0 +1,371 @@
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{
+        dm_api::{
+            atlas::{avatar_atlas::UpstreamAvatarAtlas, equipment_atlas::UpstreamEquipmentAtlas},
+            character::{
+                eidolon::AvatarRankConfig, promotion_config::AvatarPromotionConfig,
+                types::AvatarConfig,
+            },
+            character_skill::types::{AvatarSkillConfig, AvatarSkillTreeConfig},
+            equipment::{
+                equipment_config::EquipmentConfig,
+                equipment_promotion_config::EquipmentPromotionConfig,
+                equipment_skill_config::EquipmentSkillConfig,
+            },
+            equipment_skill::skill_tree_config::SkillTreeConfig,
+            item::types::Item,
+            property::config::AvatarPropertyConfig,
+            relic::{
+                config::RelicConfig, main_affix::RelicMainAffixConfig,
+                set_config::RelicSetConfig, set_skill_config::RelicSetSkillConfig,
+                sub_affix::RelicSubAffixConfig,
+            },
+            types::TextMap,
+        },
+        traits::{dataset_root, DbData, DATASET_ROOT_OVERRIDE},
+    },
+};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum Severity {
+    /// the dataset is broken and should not be served
+    Error,
+    /// the dataset is usable but some entries will look wrong
+    Warning,
+}
+
+/// parse result of a single `DbData` implementor
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct ParseCheck {
+    pub name: String,
+    pub success: bool,
+    pub entries: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// dataset the broken entry belongs to
+    pub source: String,
+    pub id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct ValidationReport {
+    /// `false` when any parse failed or any issue is an `Error`
+    pub valid: bool,
+    pub checked_at: DateTime<Utc>,
+    pub parsed: Vec<ParseCheck>,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    fn issue(
+        &mut self,
+        severity: Severity,
+        source: &str,
+        id: impl ToString,
+        message: impl Into<String>,
+    ) {
+        self.issues.push(ValidationIssue {
+            severity,
+            source: source.to_owned(),
+            id: id.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// runs `T::read()` in its own task so a panicking conversion is recorded
+    /// as a failed parse instead of taking the validation down with it. Only
+    /// the dataset directory is read, a missing file is an `Error`
+    async fn parse<T>(&mut self, name: &str) -> Option<T::TLocal>
+    where
+        T: DbData + 'static,
+        T::TLocal: 'static,
+    {
+        // task locals don't carry over to spawned tasks
+        let read = DATASET_ROOT_OVERRIDE.scope(dataset_root(), T::read());
+        let (data, error) = match tokio::spawn(read).await {
+            Ok(Ok(data)) => (Some(data), None),
+            Ok(Err(err)) => (None, Some(err.to_string())),
+            Err(join_err) => (None, Some(format!("conversion panicked: {join_err}"))),
+        };
+        if !T::to_repo().exists() {
+            self.issue(
+                Severity::Error,
+                name,
+                T::path_data(),
+                "file is missing from the dataset",
+            );
+        }
+        let entries = data
+            .as_ref()
+            .and_then(|data| serde_json::to_value(data).ok())
+            .map(|value| match value {
+                serde_json::Value::Object(map) => map.len(),
+                serde_json::Value::Array(list) => list.len(),
+                _ => 1,
+            })
+            .unwrap_or_default();
+
+        self.parsed.push(ParseCheck {
+            name: name.to_owned(),
+            success: data.is_some(),
+            entries,
+            error,
+        });
+        data
+    }
+}
+
+/// parses every dataset we serve and checks the references between them
+pub async fn validate_dataset() -> ValidationReport {
+    let mut report = ValidationReport {
+        valid: true,
+        checked_at: Utc::now(),
+        parsed: vec![],
+        issues: vec![],
+    };
+
+    let avatars = report.parse::<AvatarConfig>("AvatarConfig").await;
+    let skills = report.parse::<AvatarSkillConfig>("AvatarSkillConfig").await;
+    let eidolons = report.parse::<AvatarRankConfig>("AvatarRankConfig").await;
+    let equipments = report.parse::<EquipmentConfig>("EquipmentConfig").await;
+    let equipment_skills = report
+        .parse::<EquipmentSkillConfig>("EquipmentSkillConfig")
+        .await;
+    let relics = report.parse::<RelicConfig>("RelicConfig").await;
+    let relic_sets = report.parse::<RelicSetConfig>("RelicSetConfig").await;
+
+    report.parse::<TextMap>("TextMap").await;
+    report
+        .parse::<AvatarPromotionConfig>("AvatarPromotionConfig")
+        .await;
+    report
+        .parse::<AvatarSkillTreeConfig>("AvatarSkillTreeConfig")
+        .await;
+    report
+        .parse::<AvatarPropertyConfig>("AvatarPropertyConfig")
+        .await;
+    report
+        .parse::<EquipmentPromotionConfig>("EquipmentPromotionConfig")
+        .await;
+    report.parse::<SkillTreeConfig>("SkillTreeConfig").await;
+    report.parse::<Item>("Item").await;
+    report
+        .parse::<RelicSetSkillConfig>("RelicSetSkillConfig")
+        .await;
+    report
+        .parse::<RelicMainAffixConfig>("RelicMainAffixConfig")
+        .await;
+    report
+        .parse::<RelicSubAffixConfig>("RelicSubAffixConfig")
+        .await;
+    report.parse::<UpstreamAvatarAtlas>("AvatarAtlas").await;
+    report.parse::<UpstreamEquipmentAtlas>("EquipmentAtlas").await;
+
+    if let Some(avatars) = &avatars {
+        check_avatars(&mut report, avatars, skills.as_ref(), eidolons.as_ref());
+    }
+    if let Some(skills) = &skills {
+        for skill in skills.values().filter(|skill| skill.skill_name.is_empty()) {
+            report.issue(
+                Severity::Warning,
+                "AvatarSkillConfig",
+                skill.skill_id,
+                "skill name resolves to an empty string",
+            );
+        }
+    }
+    if let Some(equipments) = &equipments {
+        check_equipments(&mut report, equipments, equipment_skills.as_ref());
+    }
+    if let Some(relics) = &relics {
+        check_relics(&mut report, relics, relic_sets.as_ref());
+    }
+
+    report.valid =
+        report.parsed.iter().all(|check| check.success) && report.errors().next().is_none();
+    match report.valid {
+        true => info!("dataset validated, {} warnings", report.issues.len()),
+        false => warn!(
+            "dataset validation failed: {} failed parses, {} errors",
+            report.parsed.iter().filter(|check| !check.success).count(),
+            report.errors().count()
+        ),
+    }
+    report
+}
+
+/// unreleased entries are still in development upstream, broken references
+/// there are only warnings
+fn release_severity(release: bool) -> Severity {
+    match release {
+        true => Severity::Error,
+        false => Severity::Warning,
+    }
+}
+
+fn check_avatars(
+    report: &mut ValidationReport,
+    avatars: &HashMap<u32, AvatarConfig>,
+    skills: Option<&HashMap<u32, AvatarSkillConfig>>,
+    eidolons: Option<&HashMap<u32, AvatarRankConfig>>,
+) {
+    for avatar in avatars.values() {
+        let severity = release_severity(avatar.release);
+        if avatar.avatar_name.is_empty() {
+            report.issue(
+                Severity::Warning,
+                "AvatarConfig",
+                avatar.avatar_id,
+                "avatar name resolves to an empty string",
+            );
+        }
+        if let Some(skills) = skills {
+            for skill_id in avatar
+                .skill_list
+                .iter()
+                .filter(|id| !skills.contains_key(id))
+            {
+                report.issue(
+                    severity,
+                    "AvatarConfig",
+                    avatar.avatar_id,
+                    format!("skill {skill_id} is missing from AvatarSkillConfig"),
+                );
+            }
+        }
+        if let Some(eidolons) = eidolons {
+            for rank_id in avatar
+                .rank_idlist
+                .iter()
+                .filter(|id| !eidolons.contains_key(id))
+            {
+                report.issue(
+                    severity,
+                    "AvatarConfig",
+                    avatar.avatar_id,
+                    format!("eidolon {rank_id} is missing from AvatarRankConfig"),
+                );
+            }
+        }
+    }
+}
+
+fn check_equipments(
+    report: &mut ValidationReport,
+    equipments: &HashMap<u32, EquipmentConfig>,
+    equipment_skills: Option<&HashMap<u32, EquipmentSkillConfig>>,
+) {
+    for equipment in equipments.values() {
+        if equipment.equipment_name.is_empty() {
+            report.issue(
+                Severity::Warning,
+                "EquipmentConfig",
+                equipment.equipment_id,
+                "light cone name resolves to an empty string",
+            );
+        }
+        if equipment_skills.is_some_and(|skills| !skills.contains_key(&equipment.skill_id)) {
+            report.issue(
+                release_severity(equipment.release),
+                "EquipmentConfig",
+                equipment.equipment_id,
+                format!(
+                    "skill {} is missing from EquipmentSkillConfig",
+                    equipment.skill_id
+                ),
+            );
+        }
+    }
+}
+
+fn check_relics(
+    report: &mut ValidationReport,
+    relics: &HashMap<u32, RelicConfig>,
+    relic_sets: Option<&HashMap<u32, RelicSetConfig>>,
+) {
+    if let Some(relic_sets) = relic_sets {
+        // relics have no release flag of their own, a set missing from
+        // RelicSetConfig isn't released either
+        for relic in relics
+            .values()
+            .filter(|relic| !relic_sets.contains_key(&relic.set_id))
+        {
+            report.issue(
+                release_severity(false),
+                "RelicConfig",
+                relic.id,
+                format!("set {} is missing from RelicSetConfig", relic.set_id),
+            );
+        }
+        for set in relic_sets.values() {
+            if set.set_name.is_empty() {
+                report.issue(
+                    Severity::Warning,
+                    "RelicSetConfig",
+                    set.set_id,
+                    "relic set name resolves to an empty string",
+                );
+            }
+            if !relics.values().any(|relic| relic.set_id == set.set_id) {
+                report.issue(
+                    release_severity(set.release.unwrap_or_default()),
+                    "RelicSetConfig",
+                    set.set_id,
+                    "set has no relics in RelicConfig",
+                );
+            }
+        }
+    }
+}
+
+pub async fn execute() -> Result<Json<ValidationReport>, WorkerError> {
+    info!("validating dataset...");
+    Ok(Json(validate_dataset().await))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_dataset, Severity};
+    use crate::routes::honkai::traits::DATASET_ROOT_OVERRIDE;
+
+    #[tokio::test]
+    async fn validate() {
+        let report = validate_dataset().await;
+        assert_eq!(report.parsed.len(), 19);
+        assert!(report.parsed.iter().all(|check| check.success));
+        assert!(report.valid);
+    }
+
+    #[tokio::test]
+    async fn missing_files() {
+        // nothing may be filled in from the tmp dir or the remote
+        let empty = std::env::temp_dir().join("nas-ws-empty-snapshot");
+        std::fs::create_dir_all(&empty).unwrap();
+        let report = DATASET_ROOT_OVERRIDE.scope(empty, validate_dataset()).await;
+        assert!(!report.valid);
+        assert!(report.parsed.iter().all(|check| !check.success));
+        assert!(report
+            .issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error
+                && issue.id == "ExcelOutput/AvatarConfig.json"));
+    }
+}
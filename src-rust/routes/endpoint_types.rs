@@ -7,23 +7,37 @@ use vercel_runtime::{Body, Response, StatusCode};
 #[derive(Serialize, Deserialize, Debug)]
 pub struct List<T> {
     pub list: Vec<T>,
+    /// only set by paginated endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    /// items matching the filters across every page
+    pub total: usize,
+    /// `cursor` of the next page, `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 impl<T: Clone> From<Arc<[T]>> for List<T> {
     fn from(list: Arc<[T]>) -> Self {
-        List { list: list.to_vec() }
+        List::new(list.to_vec())
     }
 }
 
 impl<T> From<Vec<T>> for List<T> {
     fn from(list: Vec<T>) -> Self {
-        List { list }
+        List::new(list)
     }
 }
 
 impl<T> List<T> {
     pub fn new(list: Vec<T>) -> Self {
-        Self { list }
+        Self {
+            list,
+            pagination: None,
+        }
     }
 }
 
@@ -39,7 +53,7 @@ impl<T: Serialize> FromAxumResponse<List<T>, WorkerError, vercel_runtime::Error>
                 .status(StatusCode::OK)
                 .header("Content-Type", "application/json")
                 .body(serde_json::to_string(val)?.into())?),
-            Err(e) => Err(e.to_string().into()),
+            Err(e) => Ok(e.into()),
         }
     }
 }
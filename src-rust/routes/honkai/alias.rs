@@ -0,0 +1,303 @@
+use super::{
+    dm_api::character::types::AvatarConfig,
+    repository::repository,
+    search::{normalize, SearchIndex, SearchKind},
+    traits::DatasetCache,
+};
+use crate::{handler::error::WorkerError, routes::endpoint_types::List};
+use axum::Json;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// community nicknames shipped with the server
+const BUNDLED_ALIASES: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/aliases.json"));
+
+/// bundled aliases, VO tags and full names of the current dataset snapshot,
+/// plus `RUNTIME_ALIASES`
+static ALIAS_REGISTRY: DatasetCache<AliasRegistry> = DatasetCache::new();
+
+/// aliases `alias_extend` accepts in total until the next restart
+const RUNTIME_ALIAS_LIMIT: usize = 256;
+
+/// everything added through `alias_extend` since startup, applied again
+/// whenever the registry is rebuilt
+static RUNTIME_ALIASES: RwLock<Vec<AliasEntry>> = RwLock::new(Vec::new());
+
+/// alternate names of one entity
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct AliasEntry {
+    pub kind: SearchKind,
+    pub id: u32,
+    pub aliases: Vec<String>,
+}
+
+/// normalized alternate names to entity ids
+#[derive(Debug, Default, Clone)]
+pub struct AliasRegistry {
+    aliases: BTreeMap<(SearchKind, String), u32>,
+}
+
+impl AliasRegistry {
+    /// aliases in `assets/aliases.json`
+    pub fn bundled() -> Result<Self, WorkerError> {
+        let entries: Vec<AliasEntry> = serde_json::from_str(BUNDLED_ALIASES)?;
+        let mut registry = Self::default();
+        registry.extend(entries);
+        Ok(registry)
+    }
+
+    /// shared registry of the current dataset, built on first use
+    pub async fn global() -> Result<Arc<AliasRegistry>, WorkerError> {
+        ALIAS_REGISTRY
+            .get_or_try_init(|| async {
+                let mut registry = Self::bundled()?;
+                for avatar in repository::<AvatarConfig>().await?.list(None).await? {
+                    let id = avatar.avatar_id;
+                    registry.insert(SearchKind::Character, id, &avatar.avatar_votag);
+                    registry.insert(SearchKind::Character, id, &avatar.avatar_full_name);
+                    registry.insert(SearchKind::Character, id, &avatar.avatar_name);
+                }
+                registry.extend(runtime_aliases().clone());
+                Ok(registry)
+            })
+            .await
+    }
+
+    /// resolves `name` through the shared registry
+    pub async fn lookup(kind: SearchKind, name: &str) -> Result<Option<u32>, WorkerError> {
+        Ok(Self::global().await?.resolve(kind, name))
+    }
+
+    /// an alias already taken by another id is left alone, that id is
+    /// returned
+    pub fn insert(&mut self, kind: SearchKind, id: u32, alias: &str) -> Option<u32> {
+        let alias = normalize(alias);
+        if alias.is_empty() {
+            return None;
+        }
+        let taken = self.aliases.entry((kind, alias)).or_insert(id);
+        (*taken != id).then_some(*taken)
+    }
+
+    /// skips aliases taken by another id
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = AliasEntry>) {
+        for entry in entries {
+            for alias in entry.aliases.iter() {
+                self.insert(entry.kind, entry.id, alias);
+            }
+        }
+    }
+
+    /// like `extend`, but adds nothing when one of the aliases is taken by
+    /// another id
+    pub fn try_extend(&mut self, entries: &[AliasEntry]) -> Result<(), WorkerError> {
+        let mut extended = self.clone();
+        for entry in entries {
+            for alias in entry.aliases.iter() {
+                if let Some(taken) = extended.insert(entry.kind, entry.id, alias) {
+                    return Err(WorkerError::ParseData(format!(
+                        "alias {alias} already points to {taken}"
+                    )));
+                }
+            }
+        }
+        *self = extended;
+        Ok(())
+    }
+
+    pub fn resolve(&self, kind: SearchKind, name: &str) -> Option<u32> {
+        self.aliases.get(&(kind, normalize(name))).copied()
+    }
+
+    /// normalized aliases grouped by entity, in kind then id order
+    pub fn entries(&self) -> Vec<AliasEntry> {
+        let mut grouped: BTreeMap<(SearchKind, u32), Vec<String>> = BTreeMap::new();
+        for ((kind, alias), id) in self.aliases.iter() {
+            grouped.entry((*kind, *id)).or_default().push(alias.clone());
+        }
+        grouped
+            .into_iter()
+            .map(|((kind, id), aliases)| AliasEntry { kind, id, aliases })
+            .collect()
+    }
+}
+
+/// id of an exact alias, else of the best fuzzy match
+pub async fn resolve_name(kind: SearchKind, name: &str) -> Result<Option<u32>, WorkerError> {
+    match AliasRegistry::lookup(kind, name).await? {
+        Some(id) => Ok(Some(id)),
+        None => Ok(SearchIndex::get().await?.best(name, kind)),
+    }
+}
+
+fn runtime_aliases() -> std::sync::RwLockWriteGuard<'static, Vec<AliasEntry>> {
+    match RUNTIME_ALIASES.write() {
+        Ok(aliases) => aliases,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+pub async fn alias_list() -> Result<Json<List<AliasEntry>>, WorkerError> {
+    Ok(Json(List::new(AliasRegistry::global().await?.entries())))
+}
+
+/// adds aliases until the next restart, to ship one add it to
+/// `assets/aliases.json`. Aliases of another id are rejected, existing ones
+/// can't be moved from here. Only routed when `server.alias_extend` is set
+pub async fn alias_extend(
+    Json(List { list, .. }): Json<List<AliasEntry>>,
+) -> Result<Json<List<AliasEntry>>, WorkerError> {
+    let mut registry = AliasRegistry::clone(&*AliasRegistry::global().await?);
+    {
+        let mut runtime = runtime_aliases();
+        let added: usize = runtime
+            .iter()
+            .chain(list.iter())
+            .map(|entry| entry.aliases.len())
+            .sum();
+        if added > RUNTIME_ALIAS_LIMIT {
+            return Err(WorkerError::ParseData(format!(
+                "runtime aliases are capped at {RUNTIME_ALIAS_LIMIT}"
+            )));
+        }
+        // another extend may have landed since the registry was built
+        registry.extend(runtime.clone());
+        registry.try_extend(&list)?;
+        runtime.extend(list);
+    }
+    ALIAS_REGISTRY.clear();
+    SearchIndex::invalidate();
+    Ok(Json(List::new(registry.entries())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alias_extend, AliasEntry, AliasRegistry, RUNTIME_ALIAS_LIMIT};
+    use crate::routes::{
+        endpoint_types::List,
+        honkai::search::{SearchIndex, SearchKind},
+    };
+    use axum::Json;
+
+    #[test]
+    fn bundled() {
+        let registry = AliasRegistry::bundled().unwrap();
+        assert_eq!(registry.resolve(SearchKind::Character, "DHIL"), Some(1213));
+        assert_eq!(
+            registry.resolve(SearchKind::Character, "Imbibitor Lunae"),
+            Some(1213)
+        );
+        assert_eq!(
+            registry.resolve(SearchKind::Character, "silver wolf"),
+            Some(1006)
+        );
+        assert_eq!(registry.resolve(SearchKind::RelicSet, "DHIL"), None);
+    }
+
+    #[test]
+    fn extend() {
+        let mut registry = AliasRegistry::default();
+        registry.extend([AliasEntry {
+            kind: SearchKind::LightCone,
+            id: 23001,
+            aliases: vec!["Seele LC".into(), "".into()],
+        }]);
+        assert_eq!(
+            registry.resolve(SearchKind::LightCone, "seelelc"),
+            Some(23001)
+        );
+        assert_eq!(
+            registry.entries(),
+            [AliasEntry {
+                kind: SearchKind::LightCone,
+                id: 23001,
+                aliases: vec!["seelelc".into()],
+            }]
+        );
+
+        // taken aliases stay where they are
+        assert_eq!(
+            registry.insert(SearchKind::LightCone, 1, "seele lc"),
+            Some(23001)
+        );
+        let moved = AliasEntry {
+            kind: SearchKind::LightCone,
+            id: 1,
+            aliases: vec!["in the night".into(), "seelelc".into()],
+        };
+        assert!(registry.try_extend(&[moved]).is_err());
+        assert_eq!(
+            registry.resolve(SearchKind::LightCone, "in the night"),
+            None
+        );
+        assert_eq!(
+            registry.resolve(SearchKind::LightCone, "seelelc"),
+            Some(23001)
+        );
+    }
+
+    #[tokio::test]
+    async fn runtime_aliases() {
+        let entry = |id, alias: &str| {
+            Json(List::new(vec![AliasEntry {
+                kind: SearchKind::Character,
+                id,
+                aliases: vec![alias.into()],
+            }]))
+        };
+        alias_extend(entry(1003, "Coffee")).await.unwrap();
+        assert_eq!(
+            AliasRegistry::lookup(SearchKind::Character, "coffee")
+                .await
+                .unwrap(),
+            Some(1003)
+        );
+        let index = SearchIndex::get().await.unwrap();
+        assert_eq!(index.best("coffee", SearchKind::Character), Some(1003));
+
+        assert!(alias_extend(entry(1102, "himeko")).await.is_err());
+        assert_eq!(
+            AliasRegistry::lookup(SearchKind::Character, "himeko")
+                .await
+                .unwrap(),
+            Some(1003)
+        );
+
+        let flood = AliasEntry {
+            kind: SearchKind::Character,
+            id: 1102,
+            aliases: (0..=RUNTIME_ALIAS_LIMIT)
+                .map(|index| format!("seele{index}"))
+                .collect(),
+        };
+        assert!(alias_extend(Json(List::new(vec![flood]))).await.is_err());
+        assert_eq!(
+            AliasRegistry::lookup(SearchKind::Character, "seele0")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn dataset_aliases() {
+        // VO tag and full name of the fixture avatars
+        assert_eq!(
+            AliasRegistry::lookup(SearchKind::Character, "himeko")
+                .await
+                .unwrap(),
+            Some(1003)
+        );
+        assert_eq!(
+            AliasRegistry::lookup(SearchKind::Character, "Seele")
+                .await
+                .unwrap(),
+            Some(1102)
+        );
+    }
+}
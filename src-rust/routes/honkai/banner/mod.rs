@@ -16,14 +16,12 @@ pub mod types;
 
 #[instrument(ret, err)]
 pub async fn warp_banner_list() -> Result<Json<List<Banner>>, WorkerError> {
-    let banner_list = List {
-        list: vec![
-            Banner::char_ssr(),
-            Banner::basic_weapon(),
-            Banner::char_sr(),
-            // dev_weapon uses unreleased pity systems
-        ],
-    };
+    let banner_list = List::new(vec![
+        Banner::char_ssr(),
+        Banner::basic_weapon(),
+        Banner::char_sr(),
+        // dev_weapon uses unreleased pity systems
+    ]);
     Ok(Json(banner_list))
 }
 
@@ -0,0 +1,293 @@
+use self::types::{
+    Changelog, ChangelogQuery, ChangelogSection, EntryChange, EntryRef, FieldChange,
+};
+use crate::{
+    handler::error::WorkerError,
+    routes::{
+        cron::snapshot::{snapshot_commit, SnapshotStore},
+        honkai::{
+            dm_api::{
+                atlas::{
+                    avatar_atlas::UpstreamAvatarAtlas, equipment_atlas::UpstreamEquipmentAtlas,
+                },
+                character::{
+                    eidolon::AvatarRankConfig, promotion_config::AvatarPromotionConfig,
+                    types::AvatarConfig,
+                },
+                character_skill::types::{AvatarSkillConfig, AvatarSkillTreeConfig},
+                equipment::{
+                    equipment_config::EquipmentConfig,
+                    equipment_promotion_config::EquipmentPromotionConfig,
+                    equipment_skill_config::EquipmentSkillConfig,
+                },
+                item::types::Item,
+                property::config::AvatarPropertyConfig,
+                relic::{
+                    config::RelicConfig, main_affix::RelicMainAffixConfig,
+                    set_config::RelicSetConfig, set_skill_config::RelicSetSkillConfig,
+                    sub_affix::RelicSubAffixConfig,
+                },
+            },
+            traits::{DbData, DATASET_ROOT_OVERRIDE},
+        },
+    },
+};
+use axum::{extract::Query, Json};
+use serde_json::{Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use tracing::info;
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// fields that hold the human readable name of an entry, first match wins
+const NAME_FIELDS: [&str; 7] = [
+    "avatar_name",
+    "skill_name",
+    "equipment_name",
+    "set_name",
+    "point_name",
+    "item_name",
+    "name",
+];
+
+/// changelogs kept in `CHANGELOG_CACHE`
+const CHANGELOG_CACHE_LIMIT: usize = 16;
+
+/// snapshots never change once promoted, so neither do their changelogs
+static CHANGELOG_CACHE: Mutex<BTreeMap<(String, String), Arc<Changelog>>> =
+    Mutex::new(BTreeMap::new());
+
+/// Changelog between two dataset snapshots
+///
+/// `to` defaults to the active snapshot and `from` to the snapshot before
+/// `to`. Both accept a commit hash prefix.
+pub async fn changelog(
+    Query(ChangelogQuery { from, to }): Query<ChangelogQuery>,
+) -> Result<Json<Changelog>, WorkerError> {
+    let now = std::time::Instant::now();
+    let store = SnapshotStore::default();
+    let snapshots = store.snapshots();
+
+    let to = match to {
+        Some(to) => resolve_snapshot(&snapshots, &to)?,
+        None => store
+            .active_snapshot()
+            .ok_or(WorkerError::NotFound("active snapshot".into()))?,
+    };
+    let from = match from {
+        Some(from) => resolve_snapshot(&snapshots, &from)?,
+        None => snapshots
+            .iter()
+            .skip_while(|id| **id != to)
+            .nth(1)
+            .cloned()
+            .ok_or(WorkerError::NotFound(format!("snapshot before {to}")))?,
+    };
+
+    let key = (from.clone(), to.clone());
+    let cached = cache().get(&key).cloned();
+    if let Some(cached) = cached {
+        return Ok(Json(cached.as_ref().clone()));
+    }
+
+    let changelog = Arc::new(
+        diff_snapshots(
+            snapshot_commit(&from),
+            &store.snapshot_dir(&from),
+            snapshot_commit(&to),
+            &store.snapshot_dir(&to),
+        )
+        .await?,
+    );
+    remember(&snapshots, key, changelog.clone());
+
+    info!("[/changelog] {:?}", now.elapsed());
+    Ok(Json(changelog.as_ref().clone()))
+}
+
+fn cache() -> MutexGuard<'static, BTreeMap<(String, String), Arc<Changelog>>> {
+    match CHANGELOG_CACHE.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// caches a changelog, dropping the ones of pruned snapshots and the oldest
+/// once `CHANGELOG_CACHE_LIMIT` is reached
+fn remember(snapshots: &[String], key: (String, String), changelog: Arc<Changelog>) {
+    let mut cached = cache();
+    cached.retain(|(from, to), _| snapshots.contains(from) && snapshots.contains(to));
+    while cached.len() >= CHANGELOG_CACHE_LIMIT {
+        // ids sort oldest first
+        let Some(oldest) = cached.keys().next().cloned() else {
+            break;
+        };
+        cached.remove(&oldest);
+    }
+    cached.insert(key, changelog);
+}
+
+/// id of the snapshot whose commit hash starts with `prefix`
+fn resolve_snapshot(snapshots: &[String], prefix: &str) -> Result<String, WorkerError> {
+    let mut matches = snapshots
+        .iter()
+        .filter(|id| snapshot_commit(id).starts_with(prefix));
+    match (matches.next(), matches.next()) {
+        (Some(id), None) => Ok(id.clone()),
+        (Some(_), Some(_)) => Err(WorkerError::ParseData(format!(
+            "commit prefix {prefix} is ambiguous"
+        ))),
+        (None, _) => Err(WorkerError::NotFound(prefix.to_owned())),
+    }
+}
+
+/// diffs every `DbData` type between the two dataset directories, a file
+/// missing from either is an error rather than read from anywhere else
+pub async fn diff_snapshots(
+    from: &str,
+    from_dir: &Path,
+    to: &str,
+    to_dir: &Path,
+) -> Result<Changelog, WorkerError> {
+    let (a, b) = (from_dir, to_dir);
+    let sections = vec![
+        diff_type::<AvatarConfig>("AvatarConfig", a, b).await?,
+        diff_type::<AvatarSkillConfig>("AvatarSkillConfig", a, b).await?,
+        diff_type::<AvatarSkillTreeConfig>("AvatarSkillTreeConfig", a, b).await?,
+        diff_type::<AvatarRankConfig>("AvatarRankConfig", a, b).await?,
+        diff_type::<AvatarPromotionConfig>("AvatarPromotionConfig", a, b).await?,
+        diff_type::<AvatarPropertyConfig>("AvatarPropertyConfig", a, b).await?,
+        diff_type::<EquipmentConfig>("EquipmentConfig", a, b).await?,
+        diff_type::<EquipmentSkillConfig>("EquipmentSkillConfig", a, b).await?,
+        diff_type::<EquipmentPromotionConfig>("EquipmentPromotionConfig", a, b).await?,
+        diff_type::<RelicConfig>("RelicConfig", a, b).await?,
+        diff_type::<RelicSetConfig>("RelicSetConfig", a, b).await?,
+        diff_type::<RelicSetSkillConfig>("RelicSetSkillConfig", a, b).await?,
+        diff_type::<RelicMainAffixConfig>("RelicMainAffixConfig", a, b).await?,
+        diff_type::<RelicSubAffixConfig>("RelicSubAffixConfig", a, b).await?,
+        diff_type::<Item>("Item", a, b).await?,
+        diff_type::<UpstreamAvatarAtlas>("AvatarAtlas", a, b).await?,
+        diff_type::<UpstreamEquipmentAtlas>("EquipmentAtlas", a, b).await?,
+    ];
+
+    Ok(Changelog {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        sections: sections
+            .into_iter()
+            .filter(|section| !section.is_empty())
+            .collect(),
+    })
+}
+
+async fn diff_type<T: DbData>(
+    dataset: &str,
+    from_dir: &Path,
+    to_dir: &Path,
+) -> Result<ChangelogSection, WorkerError> {
+    let before = DATASET_ROOT_OVERRIDE
+        .scope(from_dir.to_path_buf(), T::read())
+        .await?;
+    let after = DATASET_ROOT_OVERRIDE
+        .scope(to_dir.to_path_buf(), T::read())
+        .await?;
+    Ok(diff_entries(
+        dataset,
+        serde_json::to_value(before)?,
+        serde_json::to_value(after)?,
+    ))
+}
+
+/// diffs two datasets serialized as `{ id: entry }` maps
+pub fn diff_entries(dataset: &str, before: Value, after: Value) -> ChangelogSection {
+    let (before, after) = (as_entries(before), as_entries(after));
+    let mut section = ChangelogSection {
+        dataset: dataset.to_owned(),
+        added: vec![],
+        removed: vec![],
+        changed: vec![],
+    };
+
+    let ids: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    for id in ids {
+        match (before.get(id), after.get(id)) {
+            (None, Some(entry)) => section.added.push(entry_ref(id, entry)),
+            (Some(entry), None) => section.removed.push(entry_ref(id, entry)),
+            (Some(old), Some(new)) if old != new => {
+                let mut changes = vec![];
+                diff_value(String::new(), old, new, &mut changes);
+                let EntryRef { id, name } = entry_ref(id, new);
+                section.changed.push(EntryChange { id, name, changes });
+            }
+            _ => {}
+        }
+    }
+    section
+}
+
+/// maps keep their keys as ids, lists (e.g. affixes grouped by slot) are
+/// keyed by index
+fn as_entries(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        Value::Array(list) => list
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| (index.to_string(), entry))
+            .collect(),
+        other => Map::from_iter([(String::new(), other)]),
+    }
+}
+
+fn entry_ref(id: &str, entry: &Value) -> EntryRef {
+    let name = NAME_FIELDS
+        .iter()
+        .find_map(|field| entry.get(field).and_then(Value::as_str))
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned);
+    EntryRef {
+        id: id.to_owned(),
+        name,
+    }
+}
+
+/// collects leaf level changes, `path` uses `field.nested[index]` notation
+fn diff_value(path: String, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+            for key in keys {
+                let path = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{path}.{key}"),
+                };
+                let (old, new) = (
+                    old_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                );
+                diff_value(path, old, new, changes);
+            }
+        }
+        (Value::Array(old_list), Value::Array(new_list)) => {
+            for index in 0..old_list.len().max(new_list.len()) {
+                let (old, new) = (
+                    old_list.get(index).unwrap_or(&Value::Null),
+                    new_list.get(index).unwrap_or(&Value::Null),
+                );
+                diff_value(format!("{path}[{index}]"), old, new, changes);
+            }
+        }
+        (old, new) if old != new => changes.push(FieldChange {
+            path,
+            before: old.clone(),
+            after: new.clone(),
+        }),
+        _ => {}
+    }
+}
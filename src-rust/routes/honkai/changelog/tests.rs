@@ -0,0 +1,118 @@
+use super::{
+    changelog, diff_entries,
+    types::{ChangelogQuery, FieldChange},
+};
+use crate::{builder::config::env_config, handler::error::WorkerError};
+use axum::{extract::Query, Json};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let target = to.join(path.file_name().unwrap());
+        match path.is_dir() {
+            true => copy_dir(&path, &target),
+            false => {
+                std::fs::copy(&path, &target).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn added_removed_changed() {
+    let before = json!({
+        "1001": { "avatar_name": "March 7th", "rarity": 4 },
+        "1002": { "avatar_name": "Dan Heng", "rarity": 4 },
+    });
+    let after = json!({
+        "1001": { "avatar_name": "March 7th", "rarity": 4 },
+        "1002": { "avatar_name": "Dan Heng", "rarity": 5 },
+        "1003": { "avatar_name": "Himeko", "rarity": 5 },
+    });
+    let section = diff_entries("AvatarConfig", before.clone(), after.clone());
+
+    assert_eq!(section.added.len(), 1);
+    assert_eq!(section.added[0].name.as_deref(), Some("Himeko"));
+    assert!(section.removed.is_empty());
+    assert_eq!(section.changed.len(), 1);
+    assert_eq!(section.changed[0].name.as_deref(), Some("Dan Heng"));
+    assert_eq!(
+        section.changed[0].changes,
+        vec![FieldChange {
+            path: "rarity".into(),
+            before: json!(4),
+            after: json!(5),
+        }]
+    );
+
+    let reverted = diff_entries("AvatarConfig", after, before);
+    assert_eq!(reverted.removed[0].id, "1003");
+}
+
+#[test]
+fn nested_param_list() {
+    let before = json!({
+        "100101": { "skill_name": "Frigid Cold Arrow", "param_list": [["50.00 %"], ["60.00 %"]] },
+    });
+    let after = json!({
+        "100101": { "skill_name": "Frigid Cold Arrow", "param_list": [["50.00 %"], ["65.00 %"], ["70.00 %"]] },
+    });
+    let section = diff_entries("AvatarSkillConfig", before, after);
+    let paths: Vec<&str> = section.changed[0]
+        .changes
+        .iter()
+        .map(|change| change.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["param_list[1][0]", "param_list[2]"]);
+}
+
+#[test]
+fn unchanged_is_empty() {
+    let data = json!({ "1": { "name": "Credit" } });
+    assert!(diff_entries("Item", data.clone(), data).is_empty());
+}
+
+#[tokio::test]
+async fn between_snapshots() {
+    let config = env_config();
+    let root = &config.data.snapshot_root;
+    let _ = std::fs::remove_dir_all(root);
+    copy_dir(&config.data.local_repo, &root.join("000001-aaaa"));
+    copy_dir(&config.data.local_repo, &root.join("000002-bbbb"));
+    let avatars = root.join("000002-bbbb/ExcelOutput/AvatarConfig.json");
+    let mut data: Value =
+        serde_json::from_str(&std::fs::read_to_string(&avatars).unwrap()).unwrap();
+    data["1001"]["SPNeed"]["Value"] = json!(140);
+    std::fs::write(&avatars, data.to_string()).unwrap();
+    // every file is missing, none of them may be read from `local_repo`
+    std::fs::create_dir_all(root.join("000003-cccc")).unwrap();
+    std::fs::write(root.join("ACTIVE"), "000002-bbbb").unwrap();
+
+    let query = ChangelogQuery {
+        from: None,
+        to: None,
+    };
+    let Json(log) = changelog(Query(query)).await.unwrap();
+    assert_eq!((log.from.as_str(), log.to.as_str()), ("aaaa", "bbbb"));
+    assert_eq!(log.sections.len(), 1);
+    assert_eq!(log.sections[0].dataset, "AvatarConfig");
+    assert_eq!(log.sections[0].changed[0].id, "1001");
+    assert_eq!(
+        log.sections[0].changed[0].changes,
+        vec![FieldChange {
+            path: "spneed".into(),
+            before: json!(120),
+            after: json!(140),
+        }]
+    );
+
+    let query = ChangelogQuery {
+        from: Some("aaaa".into()),
+        to: Some("cccc".into()),
+    };
+    let missing = changelog(Query(query)).await;
+    assert!(matches!(missing, Err(WorkerError::NotFound(_))));
+}
@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+pub struct ChangelogQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct Changelog {
+    /// commit hash of the older snapshot
+    pub from: String,
+    /// commit hash of the newer snapshot
+    pub to: String,
+    /// one section per dataset that changed
+    pub sections: Vec<ChangelogSection>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct ChangelogSection {
+    pub dataset: String,
+    pub added: Vec<EntryRef>,
+    pub removed: Vec<EntryRef>,
+    pub changed: Vec<EntryChange>,
+}
+
+impl ChangelogSection {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema, PartialEq)]
+pub struct EntryRef {
+    pub id: String,
+    /// resolved name of the entry, if it has one
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct EntryChange {
+    pub id: String,
+    pub name: Option<String>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema, PartialEq)]
+pub struct FieldChange {
+    /// path to the changed value, e.g. `param_list[3][1]`
+    pub path: String,
+    /// `null` when the value was added
+    pub before: Value,
+    /// `null` when the value was removed
+    pub after: Value,
+}
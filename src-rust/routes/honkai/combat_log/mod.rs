@@ -0,0 +1,142 @@
+use self::types::{
+    AttackKind, CharacterDamage, CombatLogEntry, DamageSelfDistribution, InTeamDistribution,
+    MvpAnalysis,
+};
+use crate::handler::error::WorkerError;
+use axum::Json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// running total of one kind of attack
+#[derive(Debug, Clone, Copy, Default)]
+struct HitStats {
+    damage: f64,
+    count: u32,
+    min: f64,
+    max: f64,
+}
+
+impl HitStats {
+    fn add(&mut self, damage: f64) {
+        if self.count == 0 {
+            self.min = damage;
+            self.max = damage;
+        } else {
+            self.min = self.min.min(damage);
+            self.max = self.max.max(damage);
+        }
+        self.damage += damage;
+        self.count += 1;
+    }
+
+    /// share of `total`, count, min and max
+    fn summary(&self, total: f64) -> (f32, u32, u32, u32) {
+        let share = if total > 0.0 {
+            self.damage / total
+        } else {
+            0.0
+        };
+        (
+            share as f32,
+            self.count,
+            self.min.round() as u32,
+            self.max.round() as u32,
+        )
+    }
+}
+
+/// one JSON object per line, blank lines are skipped. Turns can't go back
+pub fn parse_log(text: &str) -> Result<Vec<CombatLogEntry>, WorkerError> {
+    let mut entries: Vec<CombatLogEntry> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let line_error =
+            |message: String| WorkerError::ParseData(format!("line {}: {message}", index + 1));
+
+        let entry: CombatLogEntry =
+            serde_json::from_str(line).map_err(|err| line_error(err.to_string()))?;
+        if !entry.damage.is_finite() || entry.damage < 0.0 {
+            return Err(line_error(format!("bad damage {}", entry.damage)));
+        }
+        if let Some(last) = entries.last() {
+            if entry.turn < last.turn {
+                return Err(line_error(format!(
+                    "turn {} comes after turn {}",
+                    entry.turn, last.turn
+                )));
+            }
+        }
+        entries.push(entry);
+    }
+    if entries.is_empty() {
+        return Err(WorkerError::EmptyBody);
+    }
+    Ok(entries)
+}
+
+/// every character of the log gets an entry for every turn of the log, even
+/// the turns they dealt no damage in
+pub fn analyze(entries: &[CombatLogEntry]) -> MvpAnalysis {
+    let mut turns: BTreeMap<u32, Vec<&CombatLogEntry>> = BTreeMap::new();
+    let mut characters: BTreeSet<&str> = BTreeSet::new();
+    for entry in entries {
+        turns.entry(entry.turn).or_default().push(entry);
+        characters.insert(entry.character.as_str());
+    }
+
+    let mut stats: HashMap<&str, BTreeMap<AttackKind, HitStats>> = HashMap::new();
+    let mut data: HashMap<String, Vec<CharacterDamage>> = HashMap::new();
+    for (turn, in_turn) in turns {
+        let mut team_damage = 0.0;
+        let mut dealt_by: HashMap<&str, f64> = HashMap::new();
+        for entry in in_turn {
+            team_damage += entry.damage;
+            *dealt_by.entry(entry.character.as_str()).or_default() += entry.damage;
+            stats
+                .entry(entry.character.as_str())
+                .or_default()
+                .entry(entry.attack)
+                .or_default()
+                .add(entry.damage);
+        }
+
+        for character in characters.iter() {
+            let dealt = dealt_by.get(character).copied().unwrap_or_default();
+            let rate = if team_damage > 0.0 {
+                dealt / team_damage
+            } else {
+                0.0
+            };
+
+            let own = stats.get(character).cloned().unwrap_or_default();
+            let total: f64 = own.values().map(|hits| hits.damage).sum();
+            let summary =
+                |kind: AttackKind| own.get(&kind).copied().unwrap_or_default().summary(total);
+            data.entry(character.to_string())
+                .or_default()
+                .push(CharacterDamage {
+                    turn,
+                    team_distribution: InTeamDistribution { rate: rate as f32 },
+                    self_distribution: DamageSelfDistribution {
+                        skill: summary(AttackKind::Skill),
+                        ult: summary(AttackKind::Ult),
+                        basic: summary(AttackKind::Basic),
+                        followup: summary(AttackKind::FollowUp),
+                    },
+                });
+        }
+    }
+    MvpAnalysis { data }
+}
+
+/// analysis of a battle log sent as JSON lines
+pub async fn mvp_analysis(body: String) -> Result<Json<MvpAnalysis>, WorkerError> {
+    let entries = parse_log(&body)?;
+    Ok(Json(analyze(&entries)))
+}
@@ -0,0 +1,97 @@
+use super::{
+    analyze, mvp_analysis, parse_log,
+    types::{AttackKind, CombatLogEntry},
+};
+use crate::handler::error::WorkerError;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const LOG: &str = r#"
+{"turn": 1, "character": "Seele", "attack": "skill", "damage": 12000}
+{"turn": 1, "character": "Bronya", "attack": "basic", "damage": 3000}
+{"turn": 2, "character": "Seele", "attack": "ult", "damage": 40000}
+{"turn": 2, "character": "Seele", "attack": "skill", "damage": 10000}
+"#;
+
+#[tokio::test]
+async fn analysis() {
+    let axum::Json(analysis) = mvp_analysis(LOG.into()).await.unwrap();
+    let seele = &analysis.data["Seele"];
+    let bronya = &analysis.data["Bronya"];
+    assert_eq!(seele.len(), 2);
+    assert_eq!(bronya.len(), 2);
+
+    assert!((seele[0].team_distribution.rate - 0.8).abs() < 1e-6);
+    assert!((bronya[0].team_distribution.rate - 0.2).abs() < 1e-6);
+    assert_eq!(bronya[1].team_distribution.rate, 0.0);
+
+    // skills so far: 12000 and 10000 out of 62000
+    let (share, count, min, max) = seele[1].self_distribution.skill;
+    assert!((share - 22.0 / 62.0).abs() < 1e-6);
+    assert_eq!((count, min, max), (2, 10000, 12000));
+    assert_eq!(seele[1].self_distribution.followup, (0.0, 0, 0, 0));
+}
+
+#[test]
+fn validation() {
+    let bad_json = parse_log("{\"turn\": 1}");
+    assert!(
+        matches!(bad_json, Err(WorkerError::ParseData(message)) if message.starts_with("line 1"))
+    );
+
+    let backwards = "{\"turn\": 2, \"character\": \"a\", \"attack\": \"basic\", \"damage\": 1}\n\
+                     {\"turn\": 1, \"character\": \"a\", \"attack\": \"basic\", \"damage\": 1}";
+    let backwards = parse_log(backwards);
+    assert!(
+        matches!(backwards, Err(WorkerError::ParseData(message)) if message.starts_with("line 2"))
+    );
+
+    let negative = "{\"turn\": 1, \"character\": \"a\", \"attack\": \"basic\", \"damage\": -1}";
+    assert!(parse_log(negative).is_err());
+    assert!(matches!(parse_log("\n\n"), Err(WorkerError::EmptyBody)));
+}
+
+/// random battle like the old mock endpoint served, seeded so every run
+/// checks the same log
+fn mock_log() -> Vec<CombatLogEntry> {
+    let mut rng = StdRng::seed_from_u64(47);
+    let names = ["Qingque", "Silver Wolf", "Natasha", "Bronya"];
+    let kinds = [
+        AttackKind::Basic,
+        AttackKind::Skill,
+        AttackKind::Ult,
+        AttackKind::FollowUp,
+    ];
+    let mut entries: Vec<CombatLogEntry> = (0..200)
+        .map(|_| CombatLogEntry {
+            turn: rng.gen_range(1..50),
+            character: names[rng.gen_range(0..names.len())].into(),
+            attack: kinds[rng.gen_range(0..kinds.len())],
+            damage: rng.gen_range(1000.0..80000.0),
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.turn);
+    entries
+}
+
+#[test]
+fn shares_add_up() {
+    let entries = mock_log();
+    let analysis = analyze(&entries);
+    let turns = analysis.data.values().next().unwrap().len();
+
+    for index in 0..turns {
+        let team: f32 = analysis
+            .data
+            .values()
+            .map(|turns| turns[index].team_distribution.rate)
+            .sum();
+        assert!((team - 1.0).abs() < 1e-4);
+    }
+    for turns in analysis.data.values() {
+        let last = &turns.last().unwrap().self_distribution;
+        let own = last.skill.0 + last.ult.0 + last.basic.0 + last.followup.0;
+        if last.skill.1 + last.ult.1 + last.basic.1 + last.followup.1 > 0 {
+            assert!((own - 1.0).abs() < 1e-4);
+        }
+    }
+}
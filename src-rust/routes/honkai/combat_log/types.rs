@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// a line of a battle log
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, JsonSchema)]
+pub struct CombatLogEntry {
+    pub turn: u32,
+    pub character: String,
+    pub attack: AttackKind,
+    pub damage: f64,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackKind {
+    Basic,
+    Skill,
+    Ult,
+    FollowUp,
+}
+
+/// damage analysis of a battle, keyed by character name with one entry per
+/// turn of the log
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct MvpAnalysis {
+    pub data: HashMap<String, Vec<CharacterDamage>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct CharacterDamage {
+    pub turn: u32,
+    /// share of the team damage of this turn
+    pub team_distribution: InTeamDistribution,
+    /// attacks of the character up to and including this turn
+    pub self_distribution: DamageSelfDistribution,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct DamageSelfDistribution {
+    // % dist, count, min, max
+    pub skill: (f32, u32, u32, u32),
+    pub ult: (f32, u32, u32, u32),
+    pub basic: (f32, u32, u32, u32),
+    pub followup: (f32, u32, u32, u32),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct InTeamDistribution {
+    pub rate: f32,
+}
@@ -22,7 +22,7 @@ pub mod shared {
 #[tonic::async_trait]
 impl SignatureAtlasService for SignatureAtlas {
     async fn list(&self, _: Request<()>) -> Result<Response<SignatureReturns>, Status> {
-        let Json(List { list }) = atlas_list().await?;
+        let Json(List { list, .. }) = atlas_list().await?;
         let list = SignatureReturns {
             list: list.into_iter().map(|e| e.into()).collect(),
         };
@@ -35,7 +35,7 @@ impl SignatureAtlasService for SignatureAtlas {
         request: Request<CharId>,
     ) -> Result<Response<SignatureReturn>, Status> {
         let CharId { char_id } = request.into_inner();
-        let Json(List { list }) = atlas_list().await?;
+        let Json(List { list, .. }) = atlas_list().await?;
         let ret = list.into_iter().find(|e| e.char_id == char_id);
         match ret {
             Some(ret) => Ok(Response::new(ret.into())),
@@ -0,0 +1,118 @@
+use self::types::{
+    Challenge, ChallengeFloor, ChallengeGroupConfig, ChallengeMazeConfig, ChallengeSide, MazeBuff,
+    ScheduleDataChallengeMaze, StageConfig, WaveEnemy,
+};
+use super::{monster::types::MonsterConfig, types::Element};
+use crate::{
+    handler::error::WorkerError,
+    routes::{endpoint_types::List, honkai::traits::DbData},
+};
+use axum::{extract::Path, Json};
+use chrono::Utc;
+
+pub mod schedule;
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// every rotation, ordered by opening date with the permanent ones last
+pub async fn challenges() -> Result<Vec<Challenge>, WorkerError> {
+    let groups = ChallengeGroupConfig::read().await?;
+    let floors = ChallengeMazeConfig::read().await?;
+    let schedules = ScheduleDataChallengeMaze::read().await?;
+    let stages = StageConfig::read().await?;
+    let buffs = MazeBuff::read().await?;
+    let monsters = MonsterConfig::read().await?;
+
+    let side = |recommended: &[Element], events: &[u32]| {
+        let stages: Vec<&StageConfig> = events.iter().filter_map(|id| stages.get(id)).collect();
+        ChallengeSide {
+            recommended: recommended.to_vec(),
+            level: stages
+                .iter()
+                .map(|stage| stage.level)
+                .max()
+                .unwrap_or_default(),
+            waves: stages
+                .iter()
+                .flat_map(|stage| stage.monster_list.iter())
+                .map(|wave| {
+                    wave.iter()
+                        .map(|monster_id| WaveEnemy {
+                            monster_id: *monster_id,
+                            name: monsters
+                                .get(monster_id)
+                                .map(|monster| monster.monster_name.clone())
+                                .unwrap_or_default(),
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    };
+
+    let mut challenges: Vec<Challenge> = groups
+        .into_values()
+        .map(|group| {
+            let mut group_floors: Vec<&ChallengeMazeConfig> = floors
+                .values()
+                .filter(|floor| floor.group_id == group.group_id)
+                .collect();
+            group_floors.sort_by_key(|floor| floor.floor);
+
+            Challenge {
+                group_id: group.group_id,
+                name: group.group_name,
+                schedule: group
+                    .schedule_data_id
+                    .and_then(|id| schedules.get(&id).copied()),
+                turbulence: buffs.get(&group.maze_buff_id).cloned(),
+                floors: group_floors
+                    .into_iter()
+                    .map(|floor| ChallengeFloor {
+                        id: floor.id,
+                        name: floor.name.clone(),
+                        floor: floor.floor,
+                        cycle_limit: floor.cycle_limit,
+                        buff: buffs.get(&floor.maze_buff_id).cloned(),
+                        first_half: side(&floor.damage_type1, &floor.event_idlist1),
+                        second_half: side(&floor.damage_type2, &floor.event_idlist2),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    challenges.sort_by_key(|challenge| {
+        (
+            challenge.schedule.is_none(),
+            challenge.schedule.map(|schedule| schedule.begin_time),
+            challenge.group_id,
+        )
+    });
+    Ok(challenges)
+}
+
+pub async fn challenge_list() -> Result<Json<List<Challenge>>, WorkerError> {
+    Ok(Json(List::new(challenges().await?)))
+}
+
+pub async fn challenge(Path(group_id): Path<u32>) -> Result<Json<Challenge>, WorkerError> {
+    challenges()
+        .await?
+        .into_iter()
+        .find(|challenge| challenge.group_id == group_id)
+        .map(Json)
+        .ok_or(WorkerError::NotFound(group_id.to_string()))
+}
+
+/// the rotation open right now. Windows are in server time and compared
+/// as UTC, so this can be a few hours early or late around a reset
+pub async fn challenge_current() -> Result<Json<Option<Challenge>>, WorkerError> {
+    let now = Utc::now().naive_utc();
+    let current = challenges().await?.into_iter().find(|challenge| {
+        challenge.schedule.map_or(false, |window| {
+            (window.begin_time..window.end_time).contains(&now)
+        })
+    });
+    Ok(Json(current))
+}
@@ -0,0 +1,80 @@
+use super::types::ScheduleDataChallengeMaze;
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{jade_estimate::types::Server, traits::DbData},
+};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+/// Memory of Chaos resets of a server. Past the last known window the
+/// resets keep the length of that window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeCycle {
+    /// reset times in ascending order, never empty
+    resets: Vec<DateTime<Utc>>,
+    length: Duration,
+}
+
+impl ChallengeCycle {
+    /// every other monday since launch, for when the schedule can't be read
+    pub fn launch(server: &Server) -> Self {
+        let launch = NaiveDate::from_ymd_opt(2023, 5, 29).unwrap();
+        Self {
+            resets: vec![reset_of(launch, server)],
+            length: Duration::weeks(2),
+        }
+    }
+
+    /// `None` when there are no windows
+    pub fn from_windows(windows: &[ScheduleDataChallengeMaze], server: &Server) -> Option<Self> {
+        let mut windows = windows.to_vec();
+        windows.sort_by_key(|window| window.begin_time);
+        let last = windows.last()?;
+        let length = last.end_time.date() - last.begin_time.date();
+        let length = if length > Duration::zero() {
+            length
+        } else {
+            Duration::weeks(2)
+        };
+        let mut resets: Vec<DateTime<Utc>> = windows
+            .iter()
+            .map(|window| reset_of(window.begin_time.date(), server))
+            .collect();
+        resets.dedup();
+        Some(Self { resets, length })
+    }
+
+    /// schedule of the dataset
+    pub async fn load(server: &Server) -> Result<Self, WorkerError> {
+        let windows: Vec<ScheduleDataChallengeMaze> = ScheduleDataChallengeMaze::read()
+            .await?
+            .into_values()
+            .collect();
+        Self::from_windows(&windows, server)
+            .ok_or(WorkerError::NotFound("ScheduleDataChallengeMaze".into()))
+    }
+
+    /// number of resets in `from..=to`
+    pub fn resets_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> u32 {
+        if from > to {
+            return 0;
+        }
+        let known = self
+            .resets
+            .iter()
+            .filter(|reset| (from..=to).contains(*reset))
+            .count() as i64;
+
+        // resets after the last known one are `last + n * length`, n >= 1
+        let last = *self.resets.last().unwrap();
+        let length = self.length.num_seconds();
+        let first = ((from - last).num_seconds() + length - 1).div_euclid(length);
+        let upto = (to - last).num_seconds().div_euclid(length);
+        let extrapolated = (upto - first.max(1) + 1).max(0);
+
+        (known + extrapolated) as u32
+    }
+}
+
+fn reset_of(date: NaiveDate, server: &Server) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(server.get_utc_reset_hour(), 0, 0).unwrap())
+}
@@ -0,0 +1,53 @@
+use super::{challenge, challenges, schedule::ChallengeCycle};
+use crate::routes::honkai::{dm_api::types::Element, jade_estimate::types::Server};
+use axum::extract::Path;
+use chrono::{TimeZone, Utc};
+
+#[tokio::test]
+async fn rotations() {
+    let list = challenges().await.unwrap();
+    let ids: Vec<u32> = list.iter().map(|challenge| challenge.group_id).collect();
+    assert_eq!(ids, [1, 2, 3, 4]);
+
+    let first = &list[0];
+    assert_eq!(first.name, "Forgotten Hall: Memory of Chaos");
+    assert_eq!(
+        first.turbulence.as_ref().unwrap().buff_name,
+        "Memory Turbulence"
+    );
+    assert!(list[1].turbulence.is_none());
+
+    let floor = &first.floors[0];
+    assert_eq!(floor.name, "Memory of Chaos (I)");
+    assert_eq!(floor.cycle_limit, 20);
+    assert_eq!(floor.first_half.level, 60);
+    assert_eq!(
+        floor.first_half.recommended,
+        [Element::Physical, Element::Ice]
+    );
+    assert_eq!(floor.first_half.waves.len(), 1);
+    assert_eq!(floor.second_half.waves.len(), 2);
+    assert_eq!(floor.second_half.waves[1][0].name, "Silvermane Soldier");
+
+    assert!(challenge(Path(5)).await.is_err());
+}
+
+#[tokio::test]
+async fn schedule() {
+    let cycle = ChallengeCycle::load(&Server::Asia).await.unwrap();
+    let launch = ChallengeCycle::launch(&Server::Asia);
+
+    // the fixture follows the launch cycle until the 3 week window of 07-10
+    let from = Utc.with_ymd_and_hms(2023, 6, 10, 19, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2023, 6, 30, 18, 29, 27).unwrap();
+    assert_eq!(cycle.resets_between(from, to), 2);
+    assert_eq!(launch.resets_between(from, to), 2);
+
+    // 07-10, 07-31 and 08-21 instead of every other monday
+    let from = Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2023, 8, 22, 0, 0, 0).unwrap();
+    assert_eq!(cycle.resets_between(from, to), 3);
+    assert_eq!(launch.resets_between(from, to), 4);
+
+    assert_eq!(cycle.resets_between(to, from), 0);
+}
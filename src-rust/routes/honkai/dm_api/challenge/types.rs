@@ -0,0 +1,319 @@
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{
+        dm_api::{
+            hash::TextHash,
+            types::{Element, TextMap},
+        },
+        traits::DbData,
+    },
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamChallengeMazeConfig {
+    #[serde(alias = "ID")]
+    id: u32,
+    #[serde(alias = "Name")]
+    name: TextHash,
+    #[serde(alias = "GroupID")]
+    group_id: u32,
+    #[serde(alias = "Floor")]
+    floor: u32,
+    #[serde(alias = "ChallengeCountDown")]
+    challenge_count_down: u32,
+    #[serde(alias = "DamageType1", default)]
+    damage_type1: Vec<Element>,
+    #[serde(alias = "DamageType2", default)]
+    damage_type2: Vec<Element>,
+    #[serde(alias = "EventIDList1", default)]
+    event_idlist1: Vec<u32>,
+    #[serde(alias = "EventIDList2", default)]
+    event_idlist2: Vec<u32>,
+    #[serde(alias = "MazeBuffID", default)]
+    maze_buff_id: u32,
+}
+
+/// a floor of Memory of Chaos
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ChallengeMazeConfig {
+    pub id: u32,
+    pub name: String,
+    pub group_id: u32,
+    pub floor: u32,
+    /// cycles before the floor is failed
+    pub cycle_limit: u32,
+    /// suggested elements of the first and second half
+    pub damage_type1: Vec<Element>,
+    pub damage_type2: Vec<Element>,
+    /// stages of the first and second half
+    pub event_idlist1: Vec<u32>,
+    pub event_idlist2: Vec<u32>,
+    /// 0 when the floor has no buff of its own
+    pub maze_buff_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamChallengeGroupConfig {
+    #[serde(alias = "GroupID")]
+    group_id: u32,
+    #[serde(alias = "GroupName")]
+    group_name: TextHash,
+    #[serde(alias = "ScheduleDataID")]
+    schedule_data_id: Option<u32>,
+    #[serde(alias = "MazeBuffID", default)]
+    maze_buff_id: u32,
+}
+
+/// a Memory of Chaos rotation
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ChallengeGroupConfig {
+    pub group_id: u32,
+    pub group_name: String,
+    /// key of `ScheduleDataChallengeMaze`, permanent groups have none
+    pub schedule_data_id: Option<u32>,
+    /// memory turbulence of the rotation, 0 when there is none
+    pub maze_buff_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamScheduleDataChallengeMaze {
+    #[serde(alias = "ID")]
+    id: u32,
+    #[serde(alias = "BeginTime")]
+    begin_time: String,
+    #[serde(alias = "EndTime")]
+    end_time: String,
+}
+
+/// opening window of a rotation, in server time
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub struct ScheduleDataChallengeMaze {
+    pub id: u32,
+    pub begin_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamStageConfig {
+    #[serde(alias = "StageID")]
+    stage_id: u32,
+    #[serde(alias = "StageName")]
+    stage_name: TextHash,
+    #[serde(alias = "HardLevelGroup")]
+    hard_level_group: u32,
+    #[serde(alias = "Level")]
+    level: u32,
+    /// `Monster0`, `Monster1`.. of every wave
+    #[serde(alias = "MonsterList", default)]
+    monster_list: Vec<BTreeMap<String, u32>>,
+}
+
+/// a battle, `monster_list[n]` holds the monster ids of wave `n + 1`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct StageConfig {
+    pub stage_id: u32,
+    pub stage_name: String,
+    pub hard_level_group: u32,
+    pub level: u32,
+    pub monster_list: Vec<Vec<u32>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamMazeBuff {
+    #[serde(alias = "ID")]
+    id: u32,
+    #[serde(alias = "BuffName")]
+    buff_name: TextHash,
+    #[serde(alias = "BuffDesc")]
+    buff_desc: TextHash,
+}
+
+/// memory turbulence and other stage wide buffs
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MazeBuff {
+    pub id: u32,
+    pub buff_name: String,
+    pub buff_desc: String,
+}
+
+/// one half of a floor
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ChallengeSide {
+    pub recommended: Vec<Element>,
+    pub level: u32,
+    /// enemy ids and names of every wave
+    pub waves: Vec<Vec<WaveEnemy>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct WaveEnemy {
+    pub monster_id: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ChallengeFloor {
+    pub id: u32,
+    pub name: String,
+    pub floor: u32,
+    pub cycle_limit: u32,
+    pub buff: Option<MazeBuff>,
+    pub first_half: ChallengeSide,
+    pub second_half: ChallengeSide,
+}
+
+/// a rotation with its window, turbulence and floors in order
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Challenge {
+    pub group_id: u32,
+    pub name: String,
+    pub schedule: Option<ScheduleDataChallengeMaze>,
+    pub turbulence: Option<MazeBuff>,
+    pub floors: Vec<ChallengeFloor>,
+}
+
+#[async_trait]
+impl DbData for ChallengeMazeConfig {
+    type TUpstream = HashMap<u32, UpstreamChallengeMazeConfig>;
+    type TLocal = HashMap<u32, ChallengeMazeConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/ChallengeMazeConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let text_map = TextMap::read().await?;
+        from.into_iter()
+            .map(|(key, value)| {
+                let floor = ChallengeMazeConfig {
+                    id: value.id,
+                    name: value.name.read_from_textmap(&text_map)?,
+                    group_id: value.group_id,
+                    floor: value.floor,
+                    cycle_limit: value.challenge_count_down,
+                    damage_type1: value.damage_type1,
+                    damage_type2: value.damage_type2,
+                    event_idlist1: value.event_idlist1,
+                    event_idlist2: value.event_idlist2,
+                    maze_buff_id: value.maze_buff_id,
+                };
+                Ok((key, floor))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbData for ChallengeGroupConfig {
+    type TUpstream = HashMap<u32, UpstreamChallengeGroupConfig>;
+    type TLocal = HashMap<u32, ChallengeGroupConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/ChallengeGroupConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let text_map = TextMap::read().await?;
+        from.into_iter()
+            .map(|(key, value)| {
+                let group = ChallengeGroupConfig {
+                    group_id: value.group_id,
+                    group_name: value.group_name.read_from_textmap(&text_map)?,
+                    schedule_data_id: value.schedule_data_id,
+                    maze_buff_id: value.maze_buff_id,
+                };
+                Ok((key, group))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbData for ScheduleDataChallengeMaze {
+    type TUpstream = HashMap<u32, UpstreamScheduleDataChallengeMaze>;
+    type TLocal = HashMap<u32, ScheduleDataChallengeMaze>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/ScheduleDataChallengeMaze.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let parse = |time: &str| {
+            NaiveDateTime::parse_from_str(time, "%F %T")
+                .map_err(|_| WorkerError::ParseData(format!("bad schedule time {time}")))
+        };
+        from.into_iter()
+            .map(|(key, value)| {
+                let window = ScheduleDataChallengeMaze {
+                    id: value.id,
+                    begin_time: parse(&value.begin_time)?,
+                    end_time: parse(&value.end_time)?,
+                };
+                Ok((key, window))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbData for StageConfig {
+    type TUpstream = HashMap<u32, UpstreamStageConfig>;
+    type TLocal = HashMap<u32, StageConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/StageConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let text_map = TextMap::read().await?;
+        from.into_iter()
+            .map(|(key, value)| {
+                let stage = StageConfig {
+                    stage_id: value.stage_id,
+                    stage_name: value.stage_name.read_from_textmap(&text_map)?,
+                    hard_level_group: value.hard_level_group,
+                    level: value.level,
+                    monster_list: value
+                        .monster_list
+                        .into_iter()
+                        .map(|wave| wave.into_values().collect())
+                        .collect(),
+                };
+                Ok((key, stage))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbData for MazeBuff {
+    /// keyed by buff id then buff level
+    type TUpstream = HashMap<u32, BTreeMap<u32, UpstreamMazeBuff>>;
+    type TLocal = HashMap<u32, MazeBuff>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/MazeBuff.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let text_map = TextMap::read().await?;
+        let mut buffs = HashMap::new();
+        for (key, levels) in from {
+            // stage buffs only use their first level
+            if let Some(value) = levels.into_values().next() {
+                let buff = MazeBuff {
+                    id: value.id,
+                    buff_name: value.buff_name.read_from_textmap(&text_map)?,
+                    buff_desc: value.buff_desc.read_from_textmap(&text_map)?,
+                };
+                buffs.insert(key, buff);
+            }
+        }
+        Ok(buffs)
+    }
+}
@@ -1,6 +1,6 @@
 use super::types::{AvatarConfig, MiniItem};
 use crate::{
-    builder::{get_db_client, traits::DbAction},
+    builder::traits::DbAction,
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{
@@ -8,7 +8,7 @@ use crate::{
             hash::{HashedString, TextHash},
             types::{AssetPath, Param, TextMap},
         },
-        traits::DbData,
+        traits::{skip_bad_record, DbData},
     },
 };
 use async_trait::async_trait;
@@ -58,6 +58,13 @@ pub struct AvatarRankConfig {
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct SkillAddLevelList(HashMap<u32, u32>);
 
+impl SkillAddLevelList {
+    /// levels added to `skill_id`, 0 when the eidolon doesn't touch it
+    pub fn get(&self, skill_id: u32) -> u32 {
+        self.0.get(&skill_id).copied().unwrap_or_default()
+    }
+}
+
 #[async_trait]
 impl DbData for AvatarRankConfig {
     type TUpstream = HashMap<u32, UpstreamAvatarRankConfig>;
@@ -73,11 +80,10 @@ impl DbData for AvatarRankConfig {
         let transformed = from
             .into_iter()
             .map(|(k, v)| {
-                let unsplitted_desc = TextHash::from(v.desc.clone())
-                    .read_from_textmap(&text_map)
-                    .unwrap();
+                let unsplitted_desc =
+                    TextHash::from(v.desc.clone()).read_from_textmap(&text_map)?;
                 let sorted_params: Vec<String> =
-                    get_sorted_params(v.param.iter().map(|e| e.value).collect(), &unsplitted_desc)
+                    get_sorted_params(v.param.iter().map(|e| e.value).collect(), &unsplitted_desc)?
                         .iter()
                         .map(|e| e.to_string())
                         .collect();
@@ -95,8 +101,9 @@ impl DbData for AvatarRankConfig {
                     unlock_cost: v.unlock_cost,
                     param: sorted_params,
                 };
-                (k, data)
+                Ok((k, data))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
         Ok(transformed)
     }
@@ -104,13 +111,16 @@ impl DbData for AvatarRankConfig {
 
 #[async_trait]
 impl DbAction for AvatarRankConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_eidolon", "honkai_avatarEidolon"]
+    }
 
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let eidolon_db = AvatarRankConfig::read().await?;
         let eidolon_sts = eidolon_db
             .into_values()
             .map(|v| {
+                let data = serde_json::to_string(&v).unwrap();
                 let AvatarRankConfig {
                     rank_id,
                     rank,
@@ -122,15 +132,16 @@ impl DbAction for AvatarRankConfig {
                 } = v;
                 Statement::with_args(
                     "INSERT OR REPLACE INTO honkai_eidolon (
-                        id, rank, name, desc, unlock_cost, param
-                    ) VALUES (?,?,?,?,?,?)",
+                        id, rank, name, desc, unlock_cost, param, data
+                    ) VALUES (?,?,?,?,?,?,?)",
                     args!(
                         rank_id,
                         rank,
                         name,
                         serde_json::to_string(&desc).unwrap(),
                         serde_json::to_string(&unlock_cost).unwrap(),
-                        serde_json::to_string(&param).unwrap()
+                        serde_json::to_string(&param).unwrap(),
+                        data
                     ),
                 )
             })
@@ -155,15 +166,6 @@ impl DbAction for AvatarRankConfig {
             })
             .collect::<Vec<Statement>>();
 
-        client.batch(eidolon_sts).await?;
-        client.batch(rank_map_sts).await?;
-
-        Ok(())
-    }
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_eidolon").await?;
-        client.execute("DELETE FROM honkai_avatarEidolon").await?;
-        Ok(())
+        Ok([eidolon_sts, rank_map_sts].concat())
     }
 }
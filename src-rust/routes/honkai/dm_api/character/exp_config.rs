@@ -0,0 +1,87 @@
+use crate::{handler::error::WorkerError, routes::honkai::traits::DbData};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamExpType {
+    #[serde(alias = "Exp")]
+    exp: u32,
+}
+
+/// EXP curve of an `exp_group`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ExpType {
+    pub type_id: u32,
+    /// `exp[n]` is the EXP needed to go from level `n + 1` to `n + 2`
+    pub exp: Vec<u32>,
+}
+
+impl ExpType {
+    /// EXP needed to go from level `from` to level `to`
+    pub fn exp_between(&self, from: u32, to: u32) -> u64 {
+        (from.max(1)..to)
+            .filter_map(|level| self.exp.get(level as usize - 1))
+            .map(|exp| *exp as u64)
+            .sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamAvatarExpItemConfig {
+    #[serde(alias = "ItemID")]
+    item_id: u32,
+    #[serde(alias = "Exp")]
+    exp: u32,
+}
+
+/// character EXP material
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct AvatarExpItemConfig {
+    pub item_id: u32,
+    pub exp: u32,
+}
+
+#[async_trait]
+impl DbData for ExpType {
+    type TUpstream = HashMap<u32, BTreeMap<u32, UpstreamExpType>>;
+    type TLocal = HashMap<u32, ExpType>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/ExpType.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(type_id, levels)| {
+                let exp = levels.into_values().map(|level| level.exp).collect();
+                (type_id, ExpType { type_id, exp })
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl DbData for AvatarExpItemConfig {
+    type TUpstream = HashMap<u32, UpstreamAvatarExpItemConfig>;
+    type TLocal = HashMap<u32, AvatarExpItemConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/AvatarExpItemConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(key, value)| {
+                let item = AvatarExpItemConfig {
+                    item_id: value.item_id,
+                    exp: value.exp,
+                };
+                (key, item)
+            })
+            .collect())
+    }
+}
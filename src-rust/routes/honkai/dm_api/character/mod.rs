@@ -4,16 +4,24 @@ use self::{
 };
 use crate::{
     handler::error::WorkerError,
-    routes::{endpoint_types::List, honkai::traits::DbData},
+    routes::{
+        endpoint_types::List,
+        honkai::{
+            alias::resolve_name, query::ListQuery, repository::repository, search::SearchKind,
+            traits::DbData,
+        },
+    },
+};
+use axum::{
+    extract::{Path, Query},
+    Json,
 };
-use axum::{extract::Path, Json};
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
-use regex::Regex;
 use reqwest::Method;
-use std::{collections::HashMap, sync::Arc};
+use std::collections::HashMap;
 use tracing::info;
 
 pub mod eidolon;
+pub mod exp_config;
 pub mod promotion_config;
 #[cfg(test)]
 mod tests;
@@ -24,58 +32,49 @@ pub mod rpc;
 pub async fn character(Path(character_id): Path<u32>) -> Result<Json<AvatarConfig>, WorkerError> {
     let now = std::time::Instant::now();
 
-    let avatar_db: HashMap<u32, AvatarConfig> = AvatarConfig::read().await?;
-
-    let data = avatar_db.get(&character_id).ok_or(WorkerError::EmptyBody)?;
+    let data = repository::<AvatarConfig>()
+        .await?
+        .get(character_id)
+        .await?
+        .ok_or(WorkerError::EmptyBody)?;
 
     info!("Duration: {:?}", now.elapsed());
-    Ok(Json(data.clone()))
+    Ok(Json(data))
 }
 
 pub async fn character_by_name(
     Path(character_name): Path<String>,
 ) -> Result<Json<Option<AvatarConfig>>, WorkerError> {
-    let regex = Regex::new("[^a-zA-Z0-9]").unwrap();
-    let character_name = regex.replace_all(&character_name, "").to_string();
-    let matcher = SkimMatcherV2::default();
-
-    let avatar_db: HashMap<u32, AvatarConfig> = AvatarConfig::read().await?;
-
-    let data: Vec<AvatarConfig> = avatar_db
-        .into_values()
-        .filter(|v| {
-            let fuzz_result = matcher.fuzzy_match(&v.avatar_name, &character_name);
-            fuzz_result.is_some()
-        })
-        .collect();
-    if data.is_empty() {
-        return Ok(Json(None));
-    }
+    let data = match resolve_name(SearchKind::Character, &character_name).await? {
+        Some(id) => repository::<AvatarConfig>().await?.get(id).await?,
+        None => None,
+    };
 
-    Ok(Json(data.get(0).cloned()))
+    Ok(Json(data))
 }
 
 pub async fn character_many(
     method: Method,
+    Query(query): Query<ListQuery>,
     character_ids: Option<Json<List<u32>>>,
 ) -> Result<Json<List<AvatarConfig>>, WorkerError> {
     let now = std::time::Instant::now();
 
-    let avatar_db: HashMap<u32, AvatarConfig> = AvatarConfig::read().await?;
-
     let ids = match (&method, character_ids) {
-        (&Method::POST, Some(Json(List { list }))) => Some(list),
+        (&Method::POST, Some(Json(List { list, .. }))) => Some(list),
         _ => None,
     };
 
-    let filtered: Arc<[AvatarConfig]> = avatar_db
-        .iter()
-        .filter(|(k, v)| (ids.is_none() || ids.as_ref().unwrap().contains(k)) && v.release)
-        .map(|(_, v)| v.clone())
+    let filtered: Vec<AvatarConfig> = repository::<AvatarConfig>()
+        .await?
+        .list(ids.as_deref())
+        .await?
+        .into_iter()
+        .filter(|avatar| avatar.release)
         .collect();
 
     info!("Duration: {:?}", now.elapsed());
-    Ok(Json(List::new(filtered.to_vec())))
+    Ok(Json(query.apply(filtered)?))
 }
 
 pub async fn promotion(
@@ -95,21 +94,25 @@ pub async fn promotion(
 pub async fn eidolon(
     Path(character_id): Path<u32>,
 ) -> Result<Json<List<AvatarRankConfig>>, WorkerError> {
-    let character = AvatarConfig::read().await?;
-    let eidolon_db = AvatarRankConfig::read().await?;
-    let ranks = &character
-        .get(&character_id)
+    let ranks = repository::<AvatarConfig>()
+        .await?
+        .get(character_id)
+        .await?
         .ok_or(WorkerError::NotFound(character_id.to_string()))?
         .rank_idlist;
+    let eidolon_db = repository::<AvatarRankConfig>()
+        .await?
+        .list(Some(&ranks))
+        .await?;
     let eidolons = ranks
         .iter()
         .map(|rank_id| {
             eidolon_db
-                .get(rank_id)
+                .iter()
+                .find(|eidolon| eidolon.rank_id == *rank_id)
                 .ok_or(WorkerError::NotFound(rank_id.to_string()))
                 .cloned()
-                .unwrap()
         })
-        .collect();
+        .collect::<Result<Vec<AvatarRankConfig>, WorkerError>>()?;
     Ok(Json(List::new(eidolons)))
 }
@@ -1,7 +1,10 @@
 use super::types::MiniItem;
 use crate::{
     handler::error::WorkerError,
-    routes::honkai::{dm_api::types::Param, traits::DbData},
+    routes::honkai::{
+        dm_api::types::Param,
+        traits::{skip_bad_record, DbData},
+    },
 };
 use async_trait::async_trait;
 use schemars::JsonSchema;
@@ -74,7 +77,9 @@ impl DbData for AvatarPromotionConfig {
         let local = from
             .into_iter()
             .map(|(main_key, inner_map)| {
-                let default_first = inner_map.get(&0).unwrap();
+                let default_first = inner_map.get(&0).ok_or(WorkerError::UpstreamData(format!(
+                    "avatar {main_key} has no promotion 0 entry"
+                )))?;
 
                 let data: AvatarPromotionConfig = AvatarPromotionConfig {
                     avatar_id: main_key,
@@ -96,8 +101,9 @@ impl DbData for AvatarPromotionConfig {
                     critical_damage: default_first.critical_damage.value,
                     base_aggro: default_first.base_aggro.value,
                 };
-                (main_key, data)
+                Ok((main_key, data))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
         Ok(local)
     }
@@ -1,5 +1,5 @@
 use crate::{
-    builder::{get_db_client, traits::DbAction, AsyncInto},
+    builder::{traits::DbAction, AsyncInto},
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{
@@ -67,7 +67,7 @@ pub struct AvatarConfig {
     pub avatar_id: u32,
     pub avatar_name: String,
     #[serde(skip)]
-    avatar_full_name: String,
+    pub avatar_full_name: String,
     #[serde(skip)]
     adventure_player_id: u32,
     pub avatar_votag: String,
@@ -76,8 +76,8 @@ pub struct AvatarConfig {
     json_path: AssetPath,
     pub damage_type: Element,
     pub spneed: u32,
-    #[serde(skip)]
-    exp_group: u32,
+    #[serde(default)]
+    pub exp_group: u32,
     #[serde(skip)]
     max_promotion: u8,
     #[serde(skip)]
@@ -135,10 +135,10 @@ impl DbData for AvatarConfig {
         let data = from
             .into_iter()
             .map(|(k, v)| {
-                let v = v.into_using_resource(&text_map).unwrap();
-                (k, v)
+                let v = v.into_using_resource(&text_map)?;
+                Ok((k, v))
             })
-            .collect();
+            .collect::<Result<HashMap<u32, AvatarConfig>, WorkerError>>()?;
         Ok(data)
     }
 }
@@ -260,45 +260,45 @@ impl AsyncInto<AvatarConfig> for UpstreamAvatarConfig {
 
 #[async_trait]
 impl DbAction for AvatarConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_avatar"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let avatar_db = AvatarConfig::read().await?;
         let batch_avatar: Vec<Statement> = avatar_db
             .into_values()
-            .map(
-                |AvatarConfig {
-                     avatar_id,
-                     avatar_name,
-                     avatar_votag,
-                     rarity,
-                     damage_type,
-                     avatar_base_type,
-                     spneed,
-                     ..
-                 }| {
-                    Statement::with_args(
-                        "INSERT OR REPLACE INTO honkai_avatar VALUES (?, ?, ?, ?, ?, ?, ?)",
-                        args!(
-                            avatar_id,
-                            avatar_name,
-                            rarity,
-                            avatar_votag,
-                            damage_type.to_string(),
-                            avatar_base_type.to_string(),
-                            spneed
-                        ),
-                    )
-                },
-            )
+            .map(|avatar| {
+                let data = serde_json::to_string(&avatar).unwrap();
+                let AvatarConfig {
+                    avatar_id,
+                    avatar_name,
+                    avatar_votag,
+                    rarity,
+                    damage_type,
+                    avatar_base_type,
+                    spneed,
+                    ..
+                } = avatar;
+                Statement::with_args(
+                    "INSERT OR REPLACE INTO
+                    honkai_avatar (
+                        id, name, rarity, votag, element, path, spneed, data
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    args!(
+                        avatar_id,
+                        avatar_name,
+                        rarity,
+                        avatar_votag,
+                        damage_type.to_string(),
+                        avatar_base_type.to_string(),
+                        spneed,
+                        data
+                    ),
+                )
+            })
             .collect();
 
-        client.batch(batch_avatar).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_avatar").await?;
-        Ok(())
+        Ok(batch_avatar)
     }
 }
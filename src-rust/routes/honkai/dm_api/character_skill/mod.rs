@@ -1,18 +1,137 @@
-use self::types::{AvatarSkillConfig, AvatarSkillTreeConfig};
+use self::types::{AvatarSkillConfig, AvatarSkillTreeConfig, LeveledSkill, SkillLevel};
 use crate::{
     handler::error::WorkerError,
     routes::{endpoint_types::List, honkai::traits::DbData},
 };
-use axum::{extract::Path, Json};
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
 use tracing::info;
 
-use super::character::types::AvatarConfig;
+use super::{
+    character::{eidolon::AvatarRankConfig, types::AvatarConfig},
+    desc_param::template::DescFormat,
+};
 
+#[cfg(test)]
+mod tests;
 pub mod types;
 
+#[derive(Debug, Deserialize, Default)]
+pub struct SkillLevelQuery {
+    /// eidolons unlocked
+    #[serde(default)]
+    pub eidolon: u32,
+    /// `skill_id:level` pairs split by commas, skills left out are at level 1
+    pub trace_levels: Option<String>,
+    /// markup of the rendered descriptions
+    #[serde(default)]
+    pub format: DescFormat,
+    /// fills in `{NICKNAME}`, which is kept as written without it
+    pub nickname: Option<String>,
+}
+
+fn parse_trace_levels(text: &str) -> Result<HashMap<u32, u32>, WorkerError> {
+    text.split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            let parsed = pair.split_once(':').and_then(|(id, level)| {
+                Some((id.trim().parse().ok()?, level.trim().parse().ok()?))
+            });
+            parsed.ok_or(WorkerError::ParseData(format!(
+                "bad trace level {pair}, expected skill_id:level"
+            )))
+        })
+        .collect()
+}
+
+/// `skills` at the levels of `query`, every skill has to belong to an avatar
+pub async fn level_skills(
+    skills: Vec<AvatarSkillConfig>,
+    query: &SkillLevelQuery,
+) -> Result<Vec<LeveledSkill>, WorkerError> {
+    let trace_levels = parse_trace_levels(query.trace_levels.as_deref().unwrap_or_default())?;
+    if let Some(skill_id) = trace_levels
+        .keys()
+        .find(|skill_id| !skills.iter().any(|skill| skill.skill_id == **skill_id))
+    {
+        return Err(WorkerError::ParseData(format!(
+            "skill {skill_id} isn't one of the requested skills"
+        )));
+    }
+    let avatars = AvatarConfig::read().await?;
+    let ranks = AvatarRankConfig::read().await?;
+    let traces = AvatarSkillTreeConfig::read().await?;
+    let placeholders: HashMap<String, String> = query
+        .nickname
+        .iter()
+        .map(|nickname| ("NICKNAME".to_owned(), nickname.clone()))
+        .collect();
+
+    skills
+        .into_iter()
+        .map(|config| {
+            let skill_id = config.skill_id;
+            let avatar = avatars
+                .values()
+                .find(|avatar| avatar.skill_list.contains(&skill_id))
+                .ok_or(WorkerError::NotFound(format!("avatar of skill {skill_id}")))?;
+            if query.eidolon as usize > avatar.rank_idlist.len() {
+                return Err(WorkerError::ParseData(format!(
+                    "{} has {} eidolons",
+                    avatar.avatar_name,
+                    avatar.rank_idlist.len()
+                )));
+            }
+            let bonus: u32 = avatar
+                .rank_idlist
+                .iter()
+                .filter_map(|rank_id| ranks.get(rank_id))
+                .filter(|rank| rank.rank <= query.eidolon)
+                .map(|rank| rank.skill_add_level_list.get(skill_id))
+                .sum();
+
+            // the data has levels past the in-game cap, the trace leveling the
+            // skill knows the cap
+            let trace_cap = traces
+                .values()
+                .find(|trace| trace.level_up_skill_id.contains(&skill_id))
+                .map_or(config.max_level, |trace| trace.max_level);
+            let trace_level = trace_levels.get(&skill_id).copied().unwrap_or(1);
+            if trace_level == 0 || trace_level > trace_cap {
+                return Err(WorkerError::ParseData(format!(
+                    "skill {skill_id} goes from level 1 to {trace_cap}"
+                )));
+            }
+            let max_level = (trace_cap + bonus).min(config.level.len().max(1) as u32);
+            let level = (trace_level + bonus).min(max_level);
+
+            let values = config
+                .param_values
+                .get(level as usize - 1)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let template = &config.skill_desc_template;
+            let params = template.params(values);
+            let description = template.render(query.format, values, &placeholders);
+            let effective = SkillLevel {
+                trace_level,
+                bonus,
+                level,
+                max_level,
+            };
+            Ok(LeveledSkill::new(config, effective, params, description))
+        })
+        .collect()
+}
+
 pub async fn skill(
     Path(character_id): Path<u32>,
-) -> Result<Json<List<AvatarSkillConfig>>, WorkerError> {
+    Query(query): Query<SkillLevelQuery>,
+) -> Result<Json<List<LeveledSkill>>, WorkerError> {
     let now = std::time::Instant::now();
 
     let character_db = AvatarConfig::read().await?;
@@ -23,21 +142,25 @@ pub async fn skill(
 
     let res: Vec<AvatarSkillConfig> = skills
         .into_iter()
-        .map(|skill_id| AvatarSkillConfig::read_splitted_by_skillid(skill_id).unwrap())
-        .collect();
+        .map(AvatarSkillConfig::read_splitted_by_skillid)
+        .collect::<Result<Vec<AvatarSkillConfig>, WorkerError>>()?;
+
+    let res = level_skills(res, &query).await?;
 
     info!("[/skill/:id] character_skill: {:?}", now.elapsed());
     Ok(Json(List::new(res)))
 }
 
 pub async fn skills(
+    Query(query): Query<SkillLevelQuery>,
     Json(skill_ids): Json<List<u32>>,
-) -> Result<Json<List<AvatarSkillConfig>>, WorkerError> {
+) -> Result<Json<List<LeveledSkill>>, WorkerError> {
     let res: Vec<AvatarSkillConfig> = skill_ids
         .list
         .into_iter()
-        .map(|key| AvatarSkillConfig::read_splitted_by_skillid(key).unwrap())
-        .collect();
+        .map(AvatarSkillConfig::read_splitted_by_skillid)
+        .collect::<Result<Vec<AvatarSkillConfig>, WorkerError>>()?;
+    let res = level_skills(res, &query).await?;
 
     Ok(Json(List::new(res)))
 }
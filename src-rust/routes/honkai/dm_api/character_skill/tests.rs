@@ -0,0 +1,91 @@
+use super::{
+    level_skills,
+    types::{AvatarSkillConfig, LeveledSkill},
+    SkillLevelQuery,
+};
+use crate::routes::honkai::traits::DbData;
+
+async fn march_skills() -> Vec<AvatarSkillConfig> {
+    let mut skills = AvatarSkillConfig::read().await.unwrap();
+    [100101, 100102, 100103]
+        .iter()
+        .map(|skill_id| skills.remove(skill_id).unwrap())
+        .collect()
+}
+
+fn query(eidolon: u32, trace_levels: &str) -> SkillLevelQuery {
+    SkillLevelQuery {
+        eidolon,
+        trace_levels: Some(trace_levels.into()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn base_levels() {
+    let skills = level_skills(march_skills().await, &SkillLevelQuery::default())
+        .await
+        .unwrap();
+    let skill = &skills[1];
+    assert_eq!((skill.effective.level, skill.effective.max_level), (1, 10));
+    assert_eq!(skills[0].effective.max_level, 6);
+    assert_eq!(skill.params, ["25%", "3"]);
+    assert_eq!(
+        skill.description,
+        "Increases the ATK of all allies by 25% for 3 turn(s)."
+    );
+
+    // the template and raw params are already rendered
+    let json = serde_json::to_value(skill).unwrap();
+    assert!(json.get("skill_desc_template").is_none());
+    assert!(json.get("param_values").is_none());
+    let schema = serde_json::to_value(schemars::schema_for!(LeveledSkill)).unwrap();
+    assert!(schema["properties"].get("skill_desc_template").is_none());
+    assert!(schema["properties"].get("param_values").is_none());
+
+    // older data without them still reads
+    let mut json = serde_json::to_value(&march_skills().await[1]).unwrap();
+    let config = json.as_object_mut().unwrap();
+    config.remove("skill_desc_template");
+    config.remove("param_values");
+    let config: AvatarSkillConfig = serde_json::from_value(json).unwrap();
+    assert!(config.param_values.is_empty());
+}
+
+#[tokio::test]
+async fn eidolon_levels() {
+    // E3 adds 2 levels to the skill and 1 to the basic attack, the ultimate
+    // waits for E5
+    let skills = level_skills(march_skills().await, &query(3, "100101:6,100102:10"))
+        .await
+        .unwrap();
+    let levels: Vec<(u32, u32, u32)> = skills
+        .iter()
+        .map(|skill| {
+            let effective = skill.effective;
+            (effective.bonus, effective.level, effective.max_level)
+        })
+        .collect();
+    assert_eq!(levels, [(1, 7, 7), (2, 12, 12), (0, 1, 10)]);
+    assert_eq!(skills[1].params[0], "36%");
+    assert!(skills[1].description.contains("by 36% for"));
+
+    let skills = level_skills(march_skills().await, &query(6, "100103:4"))
+        .await
+        .unwrap();
+    assert_eq!(skills[2].effective.level, 6);
+    assert_eq!(skills[2].effective.max_level, 12);
+}
+
+#[tokio::test]
+async fn validation() {
+    for bad in [
+        query(7, ""),
+        query(0, "100102:11"),
+        query(0, "100102:0"),
+        query(0, "100102:x"),
+        query(0, "110201:1"),
+    ] {
+        assert!(level_skills(march_skills().await, &bad).await.is_err());
+    }
+}
@@ -1,14 +1,14 @@
 use crate::{
-    builder::{get_db_client, traits::DbAction},
+    builder::traits::DbAction,
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{
             character::types::{AvatarConfig, MiniItem},
-            desc_param::{get_sorted_params, ParameterizedDescription},
+            desc_param::{get_sorted_params, template::DescTemplate, ParameterizedDescription},
             hash::{HashedString, TextHash},
             types::{AbilityProperty, Anchor, AssetPath, Element, Param, SkillType, TextMap},
         },
-        traits::DbData,
+        traits::{skip_bad_record, splitted_dir, DbData},
     },
 };
 use async_trait::async_trait;
@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     fs::File,
-    io::BufReader,
+    io::{BufReader, ErrorKind},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -112,6 +112,9 @@ pub struct AvatarSkillConfig {
     // WARN: unknown type, DM data is all empty
     pub level_up_cost_list: Vec<u32>,
     pub skill_desc: ParameterizedDescription,
+    /// `skill_desc` with its markup, rendered with `param_values`
+    #[serde(default)]
+    pub skill_desc_template: DescTemplate,
     pub simple_skill_desc: String,
     pub rated_skill_tree_id: Vec<u32>,
     pub rated_rank_id: Vec<u32>,
@@ -133,6 +136,10 @@ pub struct AvatarSkillConfig {
     pub skill_need: String,
     pub delay_ratio: Param,
     pub param_list: Vec<Vec<String>>,
+    /// unsorted params of every level, indexed like the slots of the
+    /// description
+    #[serde(default)]
+    pub param_values: Vec<Vec<f64>>,
     pub simple_param_list: Vec<Vec<Param>>,
     pub stance_damage_type: Option<Element>,
     pub attack_type: Option<SkillType>,
@@ -140,6 +147,151 @@ pub struct AvatarSkillConfig {
     pub skill_combo_value_delta: Option<Param>,
 }
 
+/// a skill at the level an avatar has it, with the eidolons applied. Same
+/// fields as `AvatarSkillConfig` but the template and raw params, they are
+/// already rendered into `description` and `params`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct LeveledSkill {
+    pub skill_id: u32,
+    pub skill_name: String,
+    pub skill_tag: String,
+    pub skill_type_desc: String,
+    pub level: Vec<u32>,
+    pub max_level: u32,
+    pub skill_trigger_key: String,
+    pub skill_icon: AssetPath,
+    pub ultra_skill_icon: AssetPath,
+    pub level_up_cost_list: Vec<u32>,
+    pub skill_desc: ParameterizedDescription,
+    pub simple_skill_desc: String,
+    pub rated_skill_tree_id: Vec<u32>,
+    pub rated_rank_id: Vec<u32>,
+    pub extra_effect_idlist: Vec<u32>,
+    pub simple_extra_effect_idlist: Vec<u32>,
+    pub show_stance_list: Vec<Param>,
+    pub show_damage_list: Vec<u32>,
+    pub show_heal_list: Vec<u32>,
+    pub init_cool_down: i32,
+    pub cool_down: i32,
+    pub spbase: Option<Param>,
+    pub spneed: Option<Param>,
+    pub spmultiple_ratio: Param,
+    pub bpneed: Option<Param>,
+    pub bpadd: Option<Param>,
+    pub skill_need: String,
+    pub delay_ratio: Param,
+    pub param_list: Vec<Vec<String>>,
+    pub simple_param_list: Vec<Vec<Param>>,
+    pub stance_damage_type: Option<Element>,
+    pub attack_type: Option<SkillType>,
+    pub skill_effect: SkillEffect,
+    pub skill_combo_value_delta: Option<Param>,
+    pub effective: SkillLevel,
+    /// slots of the description at the effective level, formatted like
+    /// `description` shows them
+    pub params: Vec<String>,
+    /// `skill_desc_template` at the effective level, in the requested format
+    pub description: String,
+}
+
+impl LeveledSkill {
+    pub fn new(
+        config: AvatarSkillConfig,
+        effective: SkillLevel,
+        params: Vec<String>,
+        description: String,
+    ) -> Self {
+        let AvatarSkillConfig {
+            skill_id,
+            skill_name,
+            skill_tag,
+            skill_type_desc,
+            level,
+            max_level,
+            skill_trigger_key,
+            skill_icon,
+            ultra_skill_icon,
+            level_up_cost_list,
+            skill_desc,
+            skill_desc_template: _,
+            simple_skill_desc,
+            rated_skill_tree_id,
+            rated_rank_id,
+            extra_effect_idlist,
+            simple_extra_effect_idlist,
+            show_stance_list,
+            show_damage_list,
+            show_heal_list,
+            init_cool_down,
+            cool_down,
+            spbase,
+            spneed,
+            spmultiple_ratio,
+            bpneed,
+            bpadd,
+            skill_need,
+            delay_ratio,
+            param_list,
+            param_values: _,
+            simple_param_list,
+            stance_damage_type,
+            attack_type,
+            skill_effect,
+            skill_combo_value_delta,
+        } = config;
+        Self {
+            skill_id,
+            skill_name,
+            skill_tag,
+            skill_type_desc,
+            level,
+            max_level,
+            skill_trigger_key,
+            skill_icon,
+            ultra_skill_icon,
+            level_up_cost_list,
+            skill_desc,
+            simple_skill_desc,
+            rated_skill_tree_id,
+            rated_rank_id,
+            extra_effect_idlist,
+            simple_extra_effect_idlist,
+            show_stance_list,
+            show_damage_list,
+            show_heal_list,
+            init_cool_down,
+            cool_down,
+            spbase,
+            spneed,
+            spmultiple_ratio,
+            bpneed,
+            bpadd,
+            skill_need,
+            delay_ratio,
+            param_list,
+            simple_param_list,
+            stance_damage_type,
+            attack_type,
+            skill_effect,
+            skill_combo_value_delta,
+            effective,
+            params,
+            description,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub struct SkillLevel {
+    /// level bought with traces
+    pub trace_level: u32,
+    /// levels added by eidolons
+    pub bonus: u32,
+    pub level: u32,
+    /// cap of the traces plus `bonus`
+    pub max_level: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpstreamAvatarSkillTreeConfig {
     #[serde(alias = "PointID")]
@@ -230,26 +382,34 @@ impl DbData for AvatarSkillConfig {
     ) -> Result<HashMap<u32, AvatarSkillConfig>, WorkerError> {
         let mut res: HashMap<u32, AvatarSkillConfig> = HashMap::new();
         let text_map: HashMap<String, String> = TextMap::read().await?;
-        for (k, inner_map) in skill_db.into_iter() {
-            let rest = inner_map.get(&1).unwrap().clone();
+        'skills: for (k, inner_map) in skill_db.into_iter() {
+            let rest = inner_map
+                .get(&1)
+                .cloned()
+                .ok_or(WorkerError::UpstreamData(format!(
+                    "skill {k} has no level 1 entry"
+                )));
+            let Some(rest) = skip_bad_record(Self::path_data(), rest) else {
+                continue;
+            };
             let unsplitted_desc = rest.skill_desc.read_from_textmap(&text_map)?;
 
             // merge algorithms
-            let (mut levels, mut param_lists, mut simple_param_lists) =
-                (Vec::new(), Vec::new(), Vec::new());
-            inner_map.iter().for_each(|(_, b)| {
+            let (mut levels, mut param_lists, mut param_values, mut simple_param_lists) =
+                (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+            for b in inner_map.values() {
                 levels.push(b.level);
-                let current_param: Vec<String> = get_sorted_params(
-                    b.param_list.iter().map(|e| e.value).collect(),
-                    &unsplitted_desc,
-                )
-                .iter()
-                .map(|e| e.to_string())
-                .collect();
+                let values: Vec<f64> = b.param_list.iter().map(|e| e.value).collect();
+                let sorted = get_sorted_params(values.clone(), &unsplitted_desc);
+                let Some(sorted) = skip_bad_record(Self::path_data(), sorted) else {
+                    continue 'skills;
+                };
+                let current_param: Vec<String> = sorted.iter().map(|e| e.to_string()).collect();
 
                 param_lists.push(current_param);
+                param_values.push(values);
                 simple_param_lists.push(b.simple_param_list.clone());
-            });
+            }
 
             res.insert(
                 k,
@@ -264,6 +424,7 @@ impl DbData for AvatarSkillConfig {
                     skill_icon: rest.skill_icon,
                     ultra_skill_icon: rest.ultra_skill_icon,
                     level_up_cost_list: rest.level_up_cost_list,
+                    skill_desc_template: DescTemplate::parse(&unsplitted_desc),
                     skill_desc: unsplitted_desc.into(),
                     simple_skill_desc: rest.simple_skill_desc.read_from_textmap(&text_map)?,
                     rated_skill_tree_id: rest.rated_skill_tree_id,
@@ -283,6 +444,7 @@ impl DbData for AvatarSkillConfig {
                     skill_need: rest.skill_need.read_from_textmap(&text_map)?,
                     delay_ratio: rest.delay_ratio,
                     param_list: param_lists,
+                    param_values,
                     simple_param_list: simple_param_lists,
                     stance_damage_type: rest.stance_damage_type,
                     attack_type: rest.attack_type,
@@ -296,12 +458,13 @@ impl DbData for AvatarSkillConfig {
 }
 
 impl AvatarSkillConfig {
-    /// write an `AvatarSkillConfig` to smaller chunks in `tmp`
+    /// write an `AvatarSkillConfig` to smaller chunks in `splitted_dir`
     pub async fn write_splitted() -> Result<(), WorkerError> {
         let skill_db: HashMap<u32, AvatarSkillConfig> = AvatarSkillConfig::read().await?;
-        std::fs::create_dir_all("/tmp/AvatarSkillConfigs")?;
+        let dir = splitted_dir().join("AvatarSkillConfigs");
+        std::fs::create_dir_all(&dir)?;
         for (key, value) in skill_db.into_iter() {
-            let filepath = format!("/tmp/AvatarSkillConfigs/{}.json", key);
+            let filepath = dir.join(format!("{key}.json"));
             let json_blob = serde_json::to_string(&value)?;
             // save to a new file
             std::fs::write(filepath, json_blob)?;
@@ -310,8 +473,13 @@ impl AvatarSkillConfig {
     }
 
     pub fn read_splitted_by_skillid(skill_id: u32) -> Result<Self, WorkerError> {
-        let filepath = format!("/tmp/AvatarSkillConfigs/{}.json", skill_id);
-        let file = File::open(filepath)?;
+        let filepath = splitted_dir()
+            .join("AvatarSkillConfigs")
+            .join(format!("{skill_id}.json"));
+        let file = File::open(filepath).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => WorkerError::NotFound(skill_id.to_string()),
+            _ => err.into(),
+        })?;
         let reader = BufReader::new(file);
         let data: Self = serde_json::from_reader(reader)?;
         Ok(data)
@@ -320,51 +488,55 @@ impl AvatarSkillConfig {
 
 #[async_trait]
 impl DbAction for AvatarSkillConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_skill", "honkai_avatarSkill"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let skill_db = AvatarSkillConfig::read().await?;
         let metadata_db = AvatarConfig::read().await?;
 
         let st_skills: Vec<Statement> = skill_db
             .into_values()
-            .map(
-                |AvatarSkillConfig {
-                     skill_id,
-                     skill_name,
-                     skill_tag,
-                     skill_type_desc,
-                     max_level,
-                     spbase,
-                     spneed,
-                     attack_type,
-                     skill_desc,
-                     param_list,
-                     ..
-                 }| {
-                    let skill_desc = serde_json::to_string(&skill_desc.values()).unwrap();
-                    let param_list = serde_json::to_string(&param_list).unwrap();
-
-                    Statement::with_args(
-                        "INSERT OR REPLACE INTO
-                        honkai_skill (
-                            id, name, tag, type_desc, max_level, spbase,
-                            spneed, attack_type, skill_desc, param_list
-                        ) VALUES (?,?,?,?,?,?,?,?,?,?)",
-                        args!(
-                            skill_id,
-                            skill_name,
-                            skill_tag,
-                            skill_type_desc,
-                            max_level,
-                            spbase.map(|e| e.value),
-                            spneed.map(|e| e.value),
-                            attack_type.map(|e| e.to_string()),
-                            skill_desc,
-                            param_list
-                        ),
-                    )
-                },
-            )
+            .map(|skill| {
+                let data = serde_json::to_string(&skill).unwrap();
+                let AvatarSkillConfig {
+                    skill_id,
+                    skill_name,
+                    skill_tag,
+                    skill_type_desc,
+                    max_level,
+                    spbase,
+                    spneed,
+                    attack_type,
+                    skill_desc,
+                    param_list,
+                    ..
+                } = skill;
+                let skill_desc = serde_json::to_string(&skill_desc.values()).unwrap();
+                let param_list = serde_json::to_string(&param_list).unwrap();
+
+                Statement::with_args(
+                    "INSERT OR REPLACE INTO
+                    honkai_skill (
+                        id, name, tag, type_desc, max_level, spbase,
+                        spneed, attack_type, skill_desc, param_list, data
+                    ) VALUES (?,?,?,?,?,?,?,?,?,?,?)",
+                    args!(
+                        skill_id,
+                        skill_name,
+                        skill_tag,
+                        skill_type_desc,
+                        max_level,
+                        spbase.map(|e| e.value),
+                        spneed.map(|e| e.value),
+                        attack_type.map(|e| e.to_string()),
+                        skill_desc,
+                        param_list,
+                        data
+                    ),
+                )
+            })
             .collect();
         let st_fk: Vec<Statement> = metadata_db
             .into_values()
@@ -385,16 +557,7 @@ impl DbAction for AvatarSkillConfig {
 
         let sts: Vec<Statement> = [st_skills, st_fk].into_iter().flatten().collect();
 
-        client.batch(sts).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client
-            .batch(["DELETE FROM skill", "DELETE FROM honkai_avatarSkill"])
-            .await?;
-        Ok(())
+        Ok(sts)
     }
 }
 
@@ -414,9 +577,10 @@ impl DbData for AvatarSkillTreeConfig {
         let transformed = tracetree_db
             .into_iter()
             .map(|(key, value)| {
-                let converted_value = raw_convert(value, &text_map);
-                (key, converted_value)
+                let converted_value = raw_convert(value, &text_map)?;
+                Ok((key, converted_value))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
 
         Ok(transformed)
@@ -426,8 +590,13 @@ impl DbData for AvatarSkillTreeConfig {
 fn raw_convert(
     value: BTreeMap<u32, UpstreamAvatarSkillTreeConfig>,
     text_map: &HashMap<String, String>,
-) -> AvatarSkillTreeConfig {
-    let default_first = value.get(&1).unwrap().clone();
+) -> Result<AvatarSkillTreeConfig, WorkerError> {
+    let default_first = value
+        .get(&1)
+        .ok_or(WorkerError::UpstreamData(
+            "trace has no level 1 entry".into(),
+        ))?
+        .clone();
 
     let levels: Vec<u32> = value.values().map(|big| big.level).collect();
     let mats: Vec<Vec<MiniItem>> = value
@@ -443,20 +612,20 @@ fn raw_convert(
         .map(|big| {
             let vals: Vec<f64> = big.param_list.iter().map(|e| e.value).collect();
             match vals.is_empty() {
-                true => Vec::new(),
+                true => Ok(Vec::new()),
                 false => {
                     let desc_dehashed = big.point_desc.dehash(text_map).unwrap_or_default();
-                    let current_param: Vec<String> = get_sorted_params(vals, &desc_dehashed)
+                    let current_param: Vec<String> = get_sorted_params(vals, &desc_dehashed)?
                         .iter()
                         .map(|e| e.to_string())
                         .collect();
-                    current_param
+                    Ok(current_param)
                 }
             }
         })
-        .collect();
+        .collect::<Result<Vec<Vec<String>>, WorkerError>>()?;
 
-    AvatarSkillTreeConfig {
+    Ok(AvatarSkillTreeConfig {
         point_id: default_first.point_id,
         level: levels,
         avatar_id: default_first.avatar_id,
@@ -486,16 +655,17 @@ fn raw_convert(
             .read_from_textmap(text_map)
             .unwrap_or_default(),
         param_list: params,
-    }
+    })
 }
 
 // BUG: not actually working atm
 impl AvatarSkillTreeConfig {
     pub async fn write_splitted() -> Result<(), WorkerError> {
         let tracetree_db: BTreeMap<u32, Self> = Self::read().await?;
-        std::fs::create_dir_all("/tmp/AvatarSkillTreeConfigs")?;
+        let dir = splitted_dir().join("AvatarSkillTreeConfigs");
+        std::fs::create_dir_all(&dir)?;
         for (key, value) in tracetree_db.into_iter() {
-            let filepath = format!("/tmp/AvatarSkillTreeConfigs/{}.json", key);
+            let filepath = dir.join(format!("{key}.json"));
             let json_blob = serde_json::to_string(&value)?;
             // save to a new file
             std::fs::write(filepath, json_blob)?;
@@ -505,7 +675,9 @@ impl AvatarSkillTreeConfig {
 
     #[allow(dead_code)]
     pub fn read_splitted_by_skillid(skill_id: u32) -> Result<Self, WorkerError> {
-        let filepath = format!("/tmp/AvatarSkillTreeConfigs/{}.json", skill_id);
+        let filepath = splitted_dir()
+            .join("AvatarSkillTreeConfigs")
+            .join(format!("{skill_id}.json"));
         let file = File::open(filepath)?;
         let reader = BufReader::new(file);
         let data: Self = serde_json::from_reader(reader)?;
@@ -515,12 +687,16 @@ impl AvatarSkillTreeConfig {
 
 #[async_trait]
 impl DbAction for AvatarSkillTreeConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_trace", "honkai_avatarTrace", "honkai_traceMaterial"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let db = AvatarSkillTreeConfig::read().await?;
         let st: Vec<Statement> = db
             .into_values()
             .flat_map(|trace| {
+                let data = serde_json::to_string(&trace).unwrap();
                 let promotion_limit = serde_json::to_string(&trace.avatar_promotion_limit).unwrap();
                 let pre_point = serde_json::to_string(&trace.pre_point).unwrap();
                 let point_desc = serde_json::to_string(&trace.point_desc).unwrap();
@@ -530,8 +706,9 @@ impl DbAction for AvatarSkillTreeConfig {
                     "INSERT OR REPLACE INTO
                     honkai_trace (
                         id, max_level, point_type, anchor, default_unlock,
-                        avatar_promotion_limit, pre_point, point_desc, param_list
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        avatar_promotion_limit, pre_point, point_desc, param_list,
+                        data
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                     args!(
                         trace.point_id,
                         trace.max_level,
@@ -541,7 +718,8 @@ impl DbAction for AvatarSkillTreeConfig {
                         promotion_limit,
                         pre_point,
                         point_desc,
-                        param_list
+                        param_list,
+                        data
                     ),
                 );
                 let pk_st = Statement::with_args(
@@ -576,21 +754,6 @@ impl DbAction for AvatarSkillTreeConfig {
             })
             .collect();
 
-        client.batch(st).await?;
-
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client
-            .batch([
-                "DELETE FROM honkai_trace",
-                "DELETE FROM honkai_avatarTrace",
-                "DELETE FROM honkai_traceMaterial",
-                "DELETE FROM honkai_skill",
-            ])
-            .await?;
-        Ok(())
+        Ok(st)
     }
 }
@@ -0,0 +1,390 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// a description parsed into its markup, param slots and placeholders
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct DescTemplate(pub Vec<DescNode>);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DescNode {
+    Text {
+        text: String,
+    },
+    /// `#N[i]` or `#N[fD]`, `index` counts from 0
+    Param {
+        index: usize,
+        format: ParamFormat,
+        percent: bool,
+    },
+    /// `{NICKNAME}` and the like, filled in when rendering
+    Placeholder {
+        name: String,
+    },
+    /// `<color=#f29e38ff>`, how the game highlights keywords
+    Color {
+        color: String,
+        children: Vec<DescNode>,
+    },
+    Underline {
+        children: Vec<DescNode>,
+    },
+    Italic {
+        children: Vec<DescNode>,
+    },
+    Bold {
+        children: Vec<DescNode>,
+    },
+    /// any other tag, `<unbreak>` for one, only its content is rendered
+    Group {
+        tag: String,
+        children: Vec<DescNode>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamFormat {
+    /// `[i]`, rounded to a whole number
+    Integer,
+    /// `[fD]`, D decimals
+    Fixed(u8),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DescFormat {
+    #[default]
+    Text,
+    Html,
+    Markdown,
+}
+
+enum Token {
+    Open(String, Option<String>),
+    Close(String),
+    Node(DescNode),
+}
+
+/// an open tag and what was parsed inside it so far
+struct Frame {
+    tag: Option<(String, Option<String>)>,
+    children: Vec<DescNode>,
+}
+
+impl Frame {
+    fn push_text(&mut self, text: &mut String) {
+        if !text.is_empty() {
+            self.children.push(DescNode::Text {
+                text: std::mem::take(text),
+            });
+        }
+    }
+
+    fn into_node(self) -> DescNode {
+        let children = self.children;
+        let (tag, value) = self.tag.unwrap_or_default();
+        match tag.as_str() {
+            "color" => DescNode::Color {
+                color: value.unwrap_or_default(),
+                children,
+            },
+            "u" => DescNode::Underline { children },
+            "i" => DescNode::Italic { children },
+            "b" => DescNode::Bold { children },
+            _ => DescNode::Group { tag, children },
+        }
+    }
+}
+
+impl DescTemplate {
+    /// never fails, markup that doesn't parse is kept as text and tags left
+    /// open are closed at the end
+    pub fn parse(desc: &str) -> Self {
+        let mut stack = vec![Frame {
+            tag: None,
+            children: Vec::new(),
+        }];
+        let mut text = String::new();
+        let mut rest = desc;
+
+        while let Some(next) = rest.chars().next() {
+            let Some((token, len)) = next_token(rest) else {
+                text.push(next);
+                rest = &rest[next.len_utf8()..];
+                continue;
+            };
+            rest = &rest[len..];
+            stack.last_mut().unwrap().push_text(&mut text);
+            match token {
+                Token::Node(node) => stack.last_mut().unwrap().children.push(node),
+                Token::Open(tag, value) => stack.push(Frame {
+                    tag: Some((tag, value)),
+                    children: Vec::new(),
+                }),
+                // closing tags without an opening one are dropped
+                Token::Close(tag) => {
+                    let open = stack
+                        .iter()
+                        .rposition(|frame| frame.tag.as_ref().is_some_and(|open| open.0 == tag));
+                    if let Some(open) = open {
+                        while stack.len() > open {
+                            close_frame(&mut stack);
+                        }
+                    }
+                }
+            }
+        }
+        stack.last_mut().unwrap().push_text(&mut text);
+        while stack.len() > 1 {
+            close_frame(&mut stack);
+        }
+        Self(stack.pop().unwrap().children)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// every slot in the order it appears: index of the param and whether it
+    /// is a percentage
+    pub fn slots(&self) -> Vec<(usize, bool)> {
+        let mut slots = Vec::new();
+        walk(&self.0, &mut |node| {
+            if let DescNode::Param { index, percent, .. } = node {
+                slots.push((*index, *percent));
+            }
+        });
+        slots
+    }
+
+    /// every slot in the order it appears, formatted like `render` shows it
+    pub fn params(&self, params: &[f64]) -> Vec<String> {
+        let mut formatted = Vec::new();
+        walk(&self.0, &mut |node| {
+            if let DescNode::Param {
+                index,
+                format,
+                percent,
+            } = node
+            {
+                formatted.push(format_param(params.get(*index).copied(), *format, *percent));
+            }
+        });
+        formatted
+    }
+
+    /// the text between slots, without markup. Placeholders are kept as
+    /// written
+    pub fn pieces(&self) -> Vec<String> {
+        let mut pieces = vec![String::new()];
+        walk(&self.0, &mut |node| match node {
+            DescNode::Text { text } => pieces.last_mut().unwrap().push_str(text),
+            DescNode::Placeholder { name } => {
+                pieces.last_mut().unwrap().push_str(&format!("{{{name}}}"))
+            }
+            DescNode::Param { .. } => pieces.push(String::new()),
+            _ => {}
+        });
+        pieces
+    }
+
+    /// `params` is the raw param list of one level, indexed like the slots.
+    /// Placeholders missing from `placeholders` are kept as written
+    pub fn render(
+        &self,
+        format: DescFormat,
+        params: &[f64],
+        placeholders: &HashMap<String, String>,
+    ) -> String {
+        let mut out = String::new();
+        render_nodes(&self.0, format, params, placeholders, &mut out);
+        out
+    }
+}
+
+fn close_frame(stack: &mut Vec<Frame>) {
+    let frame = stack.pop().unwrap();
+    stack.last_mut().unwrap().children.push(frame.into_node());
+}
+
+fn next_token(rest: &str) -> Option<(Token, usize)> {
+    match rest.as_bytes()[0] {
+        b'<' => tag(rest),
+        b'#' => slot(rest).map(|(node, len)| (Token::Node(node), len)),
+        b'{' => placeholder(rest).map(|(node, len)| (Token::Node(node), len)),
+        _ => None,
+    }
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// `<name>`, `<name=value>` or `</name>`
+fn tag(rest: &str) -> Option<(Token, usize)> {
+    let end = rest.find('>')?;
+    let inner = &rest[1..end];
+    if let Some(name) = inner.strip_prefix('/') {
+        return is_name(name).then(|| (Token::Close(name.into()), end + 1));
+    }
+    let (name, value) = match inner.split_once('=') {
+        Some((name, value)) if !value.contains('<') => (name, Some(value.to_owned())),
+        Some(_) => return None,
+        None => (inner, None),
+    };
+    is_name(name).then(|| (Token::Open(name.into(), value), end + 1))
+}
+
+/// `#N[i]` or `#N[fD]`, with an optional `%`
+fn slot(rest: &str) -> Option<(DescNode, usize)> {
+    let digits = rest[1..].bytes().take_while(u8::is_ascii_digit).count();
+    let index: usize = rest[1..1 + digits].parse().ok()?;
+    let after = &rest[1 + digits..];
+    let close = after.find(']')?;
+    let format = match after.get(..close)?.strip_prefix('[')? {
+        "i" => ParamFormat::Integer,
+        fixed => ParamFormat::Fixed(fixed.strip_prefix('f')?.parse().ok()?),
+    };
+    let percent = after[close + 1..].starts_with('%');
+    let len = 1 + digits + close + 1 + percent as usize;
+    Some((
+        DescNode::Param {
+            index: index.checked_sub(1)?,
+            format,
+            percent,
+        },
+        len,
+    ))
+}
+
+/// `{NAME}`
+fn placeholder(rest: &str) -> Option<(DescNode, usize)> {
+    let end = rest.find('}')?;
+    let name = &rest[1..end];
+    is_name(name).then(|| {
+        (
+            DescNode::Placeholder {
+                name: name.to_owned(),
+            },
+            end + 1,
+        )
+    })
+}
+
+fn walk(nodes: &[DescNode], visit: &mut impl FnMut(&DescNode)) {
+    for node in nodes {
+        visit(node);
+        match node {
+            DescNode::Color { children, .. }
+            | DescNode::Underline { children }
+            | DescNode::Italic { children }
+            | DescNode::Bold { children }
+            | DescNode::Group { children, .. } => walk(children, visit),
+            _ => {}
+        }
+    }
+}
+
+/// how the game shows a param, an empty string when the level has no such
+/// param
+pub fn format_param(value: Option<f64>, format: ParamFormat, percent: bool) -> String {
+    let Some(value) = value else {
+        return String::new();
+    };
+    let value = if percent { value * 100.0 } else { value };
+    let mut text = match format {
+        ParamFormat::Integer => format!("{value:.0}"),
+        ParamFormat::Fixed(decimals) => format!("{:.*}", decimals as usize, value),
+    };
+    if percent {
+        text.push('%');
+    }
+    text
+}
+
+fn escape(text: &str, format: DescFormat) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (format, c) {
+            (DescFormat::Html, '&') => out.push_str("&amp;"),
+            (DescFormat::Html, '<') => out.push_str("&lt;"),
+            (DescFormat::Html, '>') => out.push_str("&gt;"),
+            (DescFormat::Html, '"') => out.push_str("&quot;"),
+            (DescFormat::Html, '\n') => out.push_str("<br>"),
+            (DescFormat::Markdown, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn render_nodes(
+    nodes: &[DescNode],
+    format: DescFormat,
+    params: &[f64],
+    placeholders: &HashMap<String, String>,
+    out: &mut String,
+) {
+    let wrap = |open: &str, children: &[DescNode], close: &str, out: &mut String| {
+        out.push_str(open);
+        render_nodes(children, format, params, placeholders, out);
+        out.push_str(close);
+    };
+    for node in nodes {
+        match (node, format) {
+            (DescNode::Text { text }, _) => out.push_str(&escape(text, format)),
+            (
+                DescNode::Param {
+                    index,
+                    format: param_format,
+                    percent,
+                },
+                _,
+            ) => {
+                let value = params.get(*index).copied();
+                out.push_str(&escape(
+                    &format_param(value, *param_format, *percent),
+                    format,
+                ));
+            }
+            (DescNode::Placeholder { name }, _) => {
+                let value = placeholders
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{{{name}}}"));
+                out.push_str(&escape(&value, format));
+            }
+            (DescNode::Color { color, children }, DescFormat::Html) => {
+                let open = format!("<span style=\"color: {}\">", escape(color, format));
+                wrap(&open, children, "</span>", out)
+            }
+            (DescNode::Underline { children }, DescFormat::Html | DescFormat::Markdown) => {
+                wrap("<u>", children, "</u>", out)
+            }
+            (DescNode::Italic { children }, DescFormat::Html) => wrap("<i>", children, "</i>", out),
+            (DescNode::Bold { children }, DescFormat::Html) => wrap("<b>", children, "</b>", out),
+            (DescNode::Italic { children }, DescFormat::Markdown) => wrap("*", children, "*", out),
+            // markdown has no colors, keywords are made bold instead
+            (
+                DescNode::Color { children, .. } | DescNode::Bold { children },
+                DescFormat::Markdown,
+            ) => wrap("**", children, "**", out),
+            (
+                DescNode::Color { children, .. }
+                | DescNode::Underline { children }
+                | DescNode::Italic { children }
+                | DescNode::Bold { children }
+                | DescNode::Group { children, .. },
+                _,
+            ) => wrap("", children, "", out),
+        }
+    }
+}
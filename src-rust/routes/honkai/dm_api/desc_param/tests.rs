@@ -21,7 +21,7 @@ fn as_func() {
         },
     ];
 
-    let t = get_sorted_params(param_list.iter().map(|param| param.value).collect(), desc);
+    let t = get_sorted_params(param_list.iter().map(|param| param.value).collect(), desc).unwrap();
     let right = vec![
         (0.24000000022351742, true),
         (3.0, false),
@@ -33,3 +33,145 @@ fn as_func() {
     assert_eq!(left, right);
 }
 
+#[test]
+fn out_of_range() {
+    use crate::handler::error::WorkerError;
+    use axum::http::StatusCode;
+
+    // a slot past the param list is the fault of the dataset, not the client
+    let err = get_sorted_params(vec![0.5], "#1[i] and #2[i]").unwrap_err();
+    assert!(matches!(err, WorkerError::UpstreamData(_)));
+    assert_eq!(err.code(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+/// random descriptions stitched together from real and malformed slots,
+/// parsing should never panic and only fail when a slot is out of range
+#[test]
+fn random_descriptions() {
+    use super::ParameterizedDescription;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    let fragments = [
+        "Deals DMG equal to ",
+        "<color=#f29e38ff>",
+        "</color>",
+        "<unbreak>",
+        "</unbreak>",
+        "#",
+        "[i]",
+        "#[i]",
+        "#[f1]",
+        "<",
+        ">",
+        " of ATK.",
+    ];
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    for _ in 0..2000 {
+        let param_len = rng.gen_range(0..6);
+        let mut desc = String::new();
+        let mut max_slot = 0;
+        for _ in 0..rng.gen_range(0..12) {
+            match rng.gen_bool(0.3) {
+                true => {
+                    let slot: usize = rng.gen_range(1..=8);
+                    let format = ["[i]", "[i]%", "[f1]", "[f1]%"].choose(&mut rng).unwrap();
+                    max_slot = max_slot.max(slot);
+                    desc.push_str(&format!("#{slot}{format}"));
+                }
+                false => desc.push_str(fragments.choose(&mut rng).unwrap()),
+            }
+        }
+        let params: Vec<f64> = (0..param_len).map(|_| rng.gen()).collect();
+
+        let _ = ParameterizedDescription::from(desc.clone());
+        let sorted = get_sorted_params(params, &desc);
+        if max_slot <= param_len {
+            assert!(sorted.is_ok(), "{desc}");
+        }
+    }
+}
+
+#[test]
+fn template_tree() {
+    use super::template::{DescNode, DescTemplate, ParamFormat};
+
+    let template =
+        DescTemplate::parse("<color=#f29e38ff><unbreak>#10[f1]%</unbreak></color> for {NICKNAME}");
+    let param = DescNode::Param {
+        index: 9,
+        format: ParamFormat::Fixed(1),
+        percent: true,
+    };
+    let expected = vec![
+        DescNode::Color {
+            color: "#f29e38ff".into(),
+            children: vec![DescNode::Group {
+                tag: "unbreak".into(),
+                children: vec![param],
+            }],
+        },
+        DescNode::Text {
+            text: " for ".into(),
+        },
+        DescNode::Placeholder {
+            name: "NICKNAME".into(),
+        },
+    ];
+    assert_eq!(template.0, expected);
+
+    // slots past 9 take every digit
+    let params: Vec<f64> = (1..=10).map(f64::from).collect();
+    let sorted = get_sorted_params(params, "#10[i] then #2[i]%").unwrap();
+    let sorted: Vec<(f64, bool)> = sorted.iter().map(|param| param.0).collect();
+    assert_eq!(sorted, [(10.0, false), (2.0, true)]);
+}
+
+#[test]
+fn template_leniency() {
+    use super::template::{DescNode, DescTemplate};
+
+    // none of these are markup, they stay text
+    let text = "a < b, #x[i], #0[i], {not a name} and {open";
+    let template = DescTemplate::parse(text);
+    assert_eq!(template.0, [DescNode::Text { text: text.into() }]);
+
+    // stray closing tags are dropped, open ones closed at the end
+    let template = DescTemplate::parse("</i>plain <u>open");
+    assert_eq!(template.pieces(), ["plain open"]);
+    assert!(matches!(template.0[1], DescNode::Underline { .. }));
+}
+
+#[test]
+fn template_render() {
+    use super::template::{DescFormat, DescTemplate};
+    use std::collections::HashMap;
+
+    let template = DescTemplate::parse(
+        "Deals <color=#f29e38ff>#1[i]%</color> of <u>ATK</u> to <i>{NICKNAME}</i> & #2[f1] <b>*</b>",
+    );
+    let params = [0.5, 1.26];
+    let placeholders = HashMap::from([("NICKNAME".to_owned(), "Stelle".to_owned())]);
+    let render = |format| template.render(format, &params, &placeholders);
+
+    assert_eq!(
+        render(DescFormat::Text),
+        "Deals 50% of ATK to Stelle & 1.3 *"
+    );
+    assert_eq!(
+        render(DescFormat::Html),
+        "Deals <span style=\"color: #f29e38ff\">50%</span> of <u>ATK</u> to <i>Stelle</i> \
+         &amp; 1.3 <b>*</b>"
+    );
+    assert_eq!(
+        render(DescFormat::Markdown),
+        "Deals **50%** of <u>ATK</u> to *Stelle* & 1.3 **\\***"
+    );
+    assert_eq!(template.params(&params), ["50%", "1.3"]);
+
+    // missing params and placeholders
+    assert_eq!(
+        template.render(DescFormat::Text, &[], &HashMap::new()),
+        "Deals  of ATK to {NICKNAME} &  *"
+    );
+}
@@ -1,5 +1,5 @@
 use crate::{
-    builder::{get_db_client, traits::DbAction},
+    builder::traits::DbAction,
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{
@@ -149,12 +149,16 @@ impl DbData for EquipmentConfig {
 
 #[async_trait]
 impl DbAction for EquipmentConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_lightCone"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let lc_db = EquipmentConfig::read().await?;
         let sts = lc_db
             .into_values()
             .map(|lc| {
+                let data = serde_json::to_string(&lc).unwrap();
                 let EquipmentConfig {
                     equipment_id,
                     release,
@@ -171,8 +175,8 @@ impl DbAction for EquipmentConfig {
                     "INSERT OR REPLACE INTO
                     honkai_lightCone (
                         id, release, name, rarity, path, max_promotion,
-                        max_rank, skill_id
-                    ) VALUES (?,?,?,?,?,?,?,?)",
+                        max_rank, skill_id, data
+                    ) VALUES (?,?,?,?,?,?,?,?,?)",
                     args!(
                         equipment_id,
                         release as i32,
@@ -181,19 +185,13 @@ impl DbAction for EquipmentConfig {
                         avatar_base_type.to_string(),
                         max_promotion,
                         max_rank,
-                        skill_id
+                        skill_id,
+                        data
                     ),
                 )
             })
             .collect::<Vec<Statement>>();
 
-        client.batch(sts).await?;
-
-        Ok(())
-    }
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_lightCone").await?;
-        Ok(())
+        Ok(sts)
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    builder::{get_db_client, traits::DbAction},
+    builder::traits::DbAction,
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{
@@ -7,7 +7,7 @@ use crate::{
             hash::TextHash,
             types::{AbilityProperty, Param, TextMap},
         },
-        traits::DbData,
+        traits::{skip_bad_record, DbData},
     },
 };
 use async_trait::async_trait;
@@ -67,7 +67,9 @@ impl DbData for EquipmentSkillConfig {
             .iter()
             .map(|(key, inner_map)| {
                 // NOTE: iterate through inner_map > sort (done via BTreeMap) > merge merge
-                let first = inner_map.get(&1).unwrap(); // WARN: unwrap
+                let first = inner_map.get(&1).ok_or(WorkerError::UpstreamData(format!(
+                    "skill {key} has no level 1 entry"
+                )))?;
 
                 // multiple reads in `for_each`
                 let skill_desc_raw = Arc::new(
@@ -94,7 +96,7 @@ impl DbData for EquipmentSkillConfig {
                     ability_property: vec![],
                 };
 
-                inner_map.iter().for_each(|(_key, skill_config)| {
+                for skill_config in inner_map.values() {
                     next.level.push(skill_config.level);
                     let sorted_params: Vec<String> = get_sorted_params(
                         skill_config
@@ -103,17 +105,18 @@ impl DbData for EquipmentSkillConfig {
                             .map(|param| param.value)
                             .collect::<Vec<f64>>(),
                         &skill_desc_raw,
-                    )
+                    )?
                     .iter()
                     .map(|e| e.to_string())
                     .collect();
                     next.param_list.push(sorted_params);
                     next.ability_property
                         .push(skill_config.ability_property.clone());
-                });
+                }
 
-                (*key, next)
+                Ok((*key, next))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
         Ok(transformed)
     }
@@ -121,13 +124,16 @@ impl DbData for EquipmentSkillConfig {
 
 #[async_trait]
 impl DbAction for EquipmentSkillConfig {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_lightConeSkill"]
+    }
 
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let lc_db = EquipmentSkillConfig::read().await?;
         let lc_sts = lc_db
             .into_values()
             .map(|v| {
+                let data = serde_json::to_string(&v).unwrap();
                 let EquipmentSkillConfig {
                     skill_id,
                     skill_name,
@@ -139,26 +145,20 @@ impl DbAction for EquipmentSkillConfig {
                 Statement::with_args(
                     "INSERT OR REPLACE INTO
                     honkai_lightConeSkill (
-                        id, name, desc, param_list, ability_property
-                    ) VALUES (?,?,?,?,?)",
+                        id, name, desc, param_list, ability_property, data
+                    ) VALUES (?,?,?,?,?,?)",
                     args!(
                         skill_id,
                         skill_name,
                         serde_json::to_string(&skill_desc).unwrap(),
                         serde_json::to_string(&param_list).unwrap(),
                         serde_json::to_string(&ability_property).unwrap(),
+                        data
                     ),
                 )
             })
             .collect::<Vec<Statement>>();
 
-        client.batch(lc_sts).await?;
-
-        Ok(())
-    }
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_lightConeSkill").await?;
-        Ok(())
+        Ok(lc_sts)
     }
 }
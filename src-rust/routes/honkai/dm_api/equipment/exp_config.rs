@@ -0,0 +1,71 @@
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{
+        dm_api::character::exp_config::{ExpType, UpstreamExpType},
+        traits::DbData,
+    },
+};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// light cone EXP curves, same shape as the character `ExpType`
+pub struct EquipmentExpType;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamEquipmentExpItemConfig {
+    #[serde(alias = "ItemID")]
+    item_id: u32,
+    #[serde(alias = "ExpProvide")]
+    exp_provide: u32,
+    #[serde(alias = "CoinCost")]
+    coin_cost: Option<u32>,
+}
+
+/// light cone EXP material
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct EquipmentExpItemConfig {
+    pub item_id: u32,
+    pub exp_provide: u32,
+    /// credits per item fed
+    pub coin_cost: u32,
+}
+
+#[async_trait]
+impl DbData for EquipmentExpType {
+    type TUpstream = HashMap<u32, BTreeMap<u32, UpstreamExpType>>;
+    type TLocal = HashMap<u32, ExpType>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/EquipmentExpType.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        ExpType::upstream_convert(from).await
+    }
+}
+
+#[async_trait]
+impl DbData for EquipmentExpItemConfig {
+    type TUpstream = HashMap<u32, UpstreamEquipmentExpItemConfig>;
+    type TLocal = HashMap<u32, EquipmentExpItemConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/EquipmentExpItemConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(key, value)| {
+                let item = EquipmentExpItemConfig {
+                    item_id: value.item_id,
+                    exp_provide: value.exp_provide,
+                    coin_cost: value.coin_cost.unwrap_or_default(),
+                };
+                (key, item)
+            })
+            .collect())
+    }
+}
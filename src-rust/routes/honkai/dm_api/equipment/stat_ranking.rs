@@ -34,17 +34,20 @@ pub async fn stat_ranking() -> Result<Json<List<EquipmentRanking>>, WorkerError>
                     .map(|(index, tier)| tier + (add[index] * (cloned[index] as f64 - 1.0)))
                     .collect()
             };
-            let name = &equipment_config.get(&key).unwrap().equipment_name;
+            let name = &equipment_config
+                .get(&key)
+                .ok_or(WorkerError::NotFound(key.to_string()))?
+                .equipment_name;
 
-            EquipmentRanking {
+            Ok(EquipmentRanking {
                 equipment_id: key,
                 equipment_name: name.to_owned(),
                 level: v.max_level,
                 hp: clos(v.base_hp, v.base_hpadd),
                 atk: clos(v.base_attack, v.base_attack_add),
                 def: clos(v.base_defence, v.base_defence_add),
-            }
+            })
         })
-        .collect();
+        .collect::<Result<Vec<EquipmentRanking>, WorkerError>>()?;
     Ok(Json(List::new(ranking)))
 }
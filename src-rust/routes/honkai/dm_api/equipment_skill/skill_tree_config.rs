@@ -7,7 +7,7 @@ use crate::{
             hash::{HashedString, TextHash},
             types::{AbilityProperty, Anchor, AssetPath, Param, TextMap},
         },
-        traits::DbData,
+        traits::{skip_bad_record, DbData},
     },
 };
 use async_trait::async_trait;
@@ -95,7 +95,9 @@ impl DbData for SkillTreeConfig {
         let transformed = from
             .into_iter()
             .map(|(k, inner_map)| {
-                let rest = inner_map.get(&1).unwrap();
+                let rest = inner_map.get(&1).ok_or(WorkerError::UpstreamData(format!(
+                    "trace {k} has no level 1 entry"
+                )))?;
                 let unsplitted_desc = TextHash::from(rest.point_desc.clone())
                     .read_from_textmap(&text_map)
                     .unwrap_or_default();
@@ -103,7 +105,7 @@ impl DbData for SkillTreeConfig {
                 let sorted_params: Vec<String> = get_sorted_params(
                     rest.param_list.iter().map(|e| e.value).collect(),
                     &unsplitted_desc,
-                )
+                )?
                 .iter()
                 .map(|e| e.to_string())
                 .collect();
@@ -141,8 +143,9 @@ impl DbData for SkillTreeConfig {
                         .unwrap_or_default(),
                     param_list: sorted_params,
                 };
-                (k, transformed)
+                Ok((k, transformed))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
         Ok(transformed)
     }
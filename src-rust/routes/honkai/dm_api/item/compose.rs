@@ -0,0 +1,54 @@
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{dm_api::character::types::MiniItem, traits::DbData},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamItemComposeConfig {
+    #[serde(alias = "ID")]
+    id: u32,
+    #[serde(alias = "ItemID")]
+    item_id: u32,
+    #[serde(alias = "MaterialCost")]
+    material_cost: Vec<MiniItem>,
+    #[serde(alias = "CoinCost")]
+    coin_cost: Option<u32>,
+}
+
+/// synthesis recipe, `material_cost` crafts one `item_id`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemComposeConfig {
+    pub id: u32,
+    pub item_id: u32,
+    pub material_cost: Vec<MiniItem>,
+    /// credits per craft
+    pub coin_cost: u32,
+}
+
+#[async_trait]
+impl DbData for ItemComposeConfig {
+    type TUpstream = BTreeMap<u32, UpstreamItemComposeConfig>;
+    type TLocal = BTreeMap<u32, ItemComposeConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/ItemComposeConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(key, value)| {
+                let recipe = ItemComposeConfig {
+                    id: value.id,
+                    item_id: value.item_id,
+                    material_cost: value.material_cost,
+                    coin_cost: value.coin_cost.unwrap_or_default(),
+                };
+                (key, recipe)
+            })
+            .collect())
+    }
+}
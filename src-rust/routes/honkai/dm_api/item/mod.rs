@@ -1,16 +1,18 @@
 use self::types::Item;
 use crate::{
     handler::error::WorkerError,
-    routes::{endpoint_types::List, honkai::traits::DbData},
+    routes::{
+        endpoint_types::List,
+        honkai::{query::ListQuery, repository::repository},
+    },
 };
-use axum::Json;
+use axum::{extract::Query, Json};
 
+pub mod compose;
 pub mod types;
 
-pub async fn item_list() -> Result<Json<List<Item>>, WorkerError> {
-    let db = Item::read().await?;
+pub async fn item_list(Query(query): Query<ListQuery>) -> Result<Json<List<Item>>, WorkerError> {
+    let list = repository::<Item>().await?.list(None).await?;
 
-    let list = db.into_values().collect();
-
-    Ok(Json(List::new(list)))
+    Ok(Json(query.apply(list)?))
 }
@@ -1,5 +1,5 @@
 use crate::{
-    builder::{get_db_client, traits::DbAction},
+    builder::traits::DbAction,
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{hash::TextHash, types::TextMap},
@@ -13,7 +13,9 @@ use std::collections::{BTreeMap, HashMap};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, EnumString, EnumIter, Display)]
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, EnumIter, Display,
+)]
 pub enum ItemType {
     Usable,
     Mission,
@@ -21,7 +23,9 @@ pub enum ItemType {
     Virtual,
     Material,
 }
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, EnumString, EnumIter, Display)]
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, EnumString, EnumIter, Display,
+)]
 pub enum ItemSubType {
     AetherSkill,
     AetherSpirit,
@@ -51,6 +55,19 @@ pub enum ItemRarity {
     Normal,
 }
 
+impl ItemRarity {
+    /// in-game star count
+    pub fn stars(&self) -> u8 {
+        match self {
+            ItemRarity::Normal => 1,
+            ItemRarity::NotNormal => 2,
+            ItemRarity::Rare => 3,
+            ItemRarity::VeryRare => 4,
+            ItemRarity::SuperRare => 5,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct UpstreamItem {
     #[serde(alias = "ID")]
@@ -90,7 +107,7 @@ pub struct UpstreamItem {
 }
 
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Item {
     pub id: u32,
     pub item_main_type: ItemType,
@@ -175,14 +192,23 @@ impl UpstreamItem {
 
 #[async_trait]
 impl DbAction for Item {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_item"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let item_db = Item::read().await?;
         let st: Vec<Statement> = item_db
             .into_values()
             .map(|item| {
+                let data = serde_json::to_string(&item).unwrap();
                 Statement::with_args(
-                    "INSERT OR REPLACE INTO honkai_item VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    "INSERT OR REPLACE INTO
+                    honkai_item (
+                        id, name, rarity, item_type, item_sub_type,
+                        inventory_display_tag, purpose_type, desc, bgdesc,
+                        pile_limit, data
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                     args!(
                         item.id,
                         item.item_name,
@@ -193,27 +219,24 @@ impl DbAction for Item {
                         item.purpose_type,
                         item.item_desc,
                         item.item_bgdesc,
-                        item.pile_limit
+                        item.pile_limit,
+                        data
                     ),
                 )
             })
             .collect();
 
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_item").await?;
-        Ok(())
+        Ok(st)
     }
 }
 
 #[async_trait]
 impl DbAction for ItemType {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_itemType"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = ItemType::iter()
             .enumerate()
             .map(|(i, value)| {
@@ -223,21 +246,17 @@ impl DbAction for ItemType {
                 )
             })
             .collect();
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_itemType").await?;
-        Ok(())
+        Ok(st)
     }
 }
 
 #[async_trait]
 impl DbAction for ItemSubType {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_itemSubType"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = ItemSubType::iter()
             .enumerate()
             .map(|(i, value)| {
@@ -247,21 +266,17 @@ impl DbAction for ItemSubType {
                 )
             })
             .collect();
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_itemSubType").await?;
-        Ok(())
+        Ok(st)
     }
 }
 
 #[async_trait]
 impl DbAction for ItemRarity {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_itemRarity"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = ItemRarity::iter()
             .enumerate()
             .map(|(i, value)| {
@@ -271,13 +286,6 @@ impl DbAction for ItemRarity {
                 )
             })
             .collect();
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_itemRarity").await?;
-        Ok(())
+        Ok(st)
     }
 }
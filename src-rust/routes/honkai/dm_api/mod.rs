@@ -1,4 +1,5 @@
 pub mod atlas;
+pub mod challenge;
 pub mod character;
 pub mod character_skill;
 pub mod desc_param;
@@ -10,3 +11,4 @@ pub mod types;
 pub mod property;
 pub mod relic;
 pub mod item;
+pub mod monster;
@@ -0,0 +1,78 @@
+use self::types::{Enemy, HardLevelGroup, MonsterConfig, MonsterTemplateConfig};
+use crate::{
+    handler::error::WorkerError,
+    routes::{
+        endpoint_types::List,
+        honkai::{alias::resolve_name, query::ListQuery, search::SearchKind, traits::DbData},
+    },
+};
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use serde::Deserialize;
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+#[derive(Debug, Deserialize)]
+pub struct EnemyQuery {
+    #[serde(default = "default_level")]
+    pub level: u32,
+}
+
+fn default_level() -> u32 {
+    1
+}
+
+/// `monster_id` at `level`, the level must exist in its level curve
+pub async fn enemy_at_level(monster_id: u32, level: u32) -> Result<Enemy, WorkerError> {
+    let monster = MonsterConfig::read()
+        .await?
+        .remove(&monster_id)
+        .ok_or(WorkerError::NotFound(monster_id.to_string()))?;
+    let template = MonsterTemplateConfig::read()
+        .await?
+        .remove(&monster.monster_template_id)
+        .ok_or(WorkerError::NotFound(format!(
+            "template {}",
+            monster.monster_template_id
+        )))?;
+    let curve = HardLevelGroup::read()
+        .await?
+        .remove(&monster.hard_level_group)
+        .and_then(|group| group.levels.get(&level).copied())
+        .ok_or(WorkerError::NotFound(format!(
+            "level {level} of hard level group {}",
+            monster.hard_level_group
+        )))?;
+
+    Ok(Enemy::at_level(&monster, &template, &curve))
+}
+
+pub async fn enemy_list(
+    Query(query): Query<ListQuery>,
+) -> Result<Json<List<MonsterConfig>>, WorkerError> {
+    let mut monsters: Vec<MonsterConfig> = MonsterConfig::read().await?.into_values().collect();
+    monsters.sort_by_key(|monster| monster.monster_id);
+    Ok(Json(query.apply(monsters)?))
+}
+
+pub async fn enemy(
+    Path(monster_id): Path<u32>,
+    Query(query): Query<EnemyQuery>,
+) -> Result<Json<Enemy>, WorkerError> {
+    Ok(Json(enemy_at_level(monster_id, query.level).await?))
+}
+
+pub async fn enemy_search(
+    Path(name): Path<String>,
+) -> Result<Json<Option<MonsterConfig>>, WorkerError> {
+    let data = match resolve_name(SearchKind::Enemy, &name).await? {
+        Some(id) => MonsterConfig::read().await?.remove(&id),
+        None => None,
+    };
+
+    Ok(Json(data))
+}
@@ -0,0 +1,47 @@
+use super::{
+    enemy_at_level, enemy_search,
+    types::{HardLevelGroup, MonsterConfig},
+};
+use crate::routes::honkai::{dm_api::types::Element, traits::DbData};
+use axum::{extract::Path, Json};
+
+#[tokio::test]
+async fn reading() {
+    let monsters = MonsterConfig::read().await.unwrap();
+    let cocolia = &monsters[&2013010];
+    assert_eq!(cocolia.monster_name, "Cocolia, Mother of Deception");
+    assert_eq!(
+        cocolia.weaknesses,
+        [Element::Fire, Element::Physical, Element::Quantum]
+    );
+    assert_eq!(cocolia.debuff_resistances[0].key, "STAT_Confine");
+
+    let groups = HardLevelGroup::read().await.unwrap();
+    assert_eq!(groups[&1].levels.len(), 95);
+}
+
+#[tokio::test]
+async fn level_scaling() {
+    let enemy = enemy_at_level(1012010, 1).await.unwrap();
+    assert_eq!(enemy.name, "Silvermane Soldier");
+    assert!((enemy.hp - 60.0).abs() < 1e-6);
+    assert!((enemy.defence - 210.0).abs() < 1e-6);
+    assert!((enemy.speed - 100.0).abs() < 1e-6);
+    assert!((enemy.resistance(Element::Ice) - 0.2).abs() < 1e-6);
+    assert_eq!(enemy.resistance(Element::Fire), 0.0);
+
+    // DEF follows 200 + 10 * level, SPD gets the flat bonus of the variant
+    let enemy = enemy_at_level(2013010, 80).await.unwrap();
+    assert!((enemy.defence - 1000.0).abs() < 1e-3);
+    assert!((enemy.speed - 130.0).abs() < 1e-6);
+    assert!((enemy.toughness - 240.0).abs() < 1e-6);
+
+    assert!(enemy_at_level(2013010, 96).await.is_err());
+    assert!(enemy_at_level(1, 1).await.is_err());
+}
+
+#[tokio::test]
+async fn search() {
+    let Json(monster) = enemy_search(Path("cocolia".into())).await.unwrap();
+    assert_eq!(monster.unwrap().monster_id, 2013010);
+}
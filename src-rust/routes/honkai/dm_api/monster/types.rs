@@ -0,0 +1,352 @@
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{
+        dm_api::{
+            hash::TextHash,
+            types::{Element, Param, TextMap},
+        },
+        traits::DbData,
+    },
+};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamMonsterConfig {
+    #[serde(alias = "MonsterID")]
+    monster_id: u32,
+    #[serde(alias = "MonsterTemplateID")]
+    monster_template_id: u32,
+    #[serde(alias = "MonsterName")]
+    monster_name: TextHash,
+    #[serde(alias = "MonsterIntroduction")]
+    monster_introduction: Option<TextHash>,
+    #[serde(alias = "HardLevelGroup")]
+    hard_level_group: u32,
+    #[serde(alias = "EliteGroup")]
+    elite_group: u32,
+    #[serde(alias = "AttackModifyRatio")]
+    attack_modify_ratio: Param,
+    #[serde(alias = "DefenceModifyRatio")]
+    defence_modify_ratio: Param,
+    #[serde(alias = "HPModifyRatio")]
+    hpmodify_ratio: Param,
+    #[serde(alias = "SpeedModifyRatio")]
+    speed_modify_ratio: Param,
+    #[serde(alias = "StanceModifyRatio")]
+    stance_modify_ratio: Param,
+    #[serde(alias = "SpeedModifyValue")]
+    speed_modify_value: Option<Param>,
+    #[serde(alias = "StanceModifyValue")]
+    stance_modify_value: Option<Param>,
+    #[serde(alias = "DebuffResist", default)]
+    debuff_resist: Vec<UpstreamDebuffResistance>,
+    #[serde(alias = "StanceWeakList", default)]
+    stance_weak_list: Vec<Element>,
+    #[serde(alias = "DamageTypeResistance", default)]
+    damage_type_resistance: Vec<UpstreamElementResistance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamDebuffResistance {
+    #[serde(alias = "Key")]
+    key: String,
+    #[serde(alias = "Value")]
+    value: Param,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamElementResistance {
+    #[serde(alias = "DamageType")]
+    damage_type: Element,
+    #[serde(alias = "Value")]
+    value: Param,
+}
+
+/// an enemy variant, the stats of its template are scaled by the
+/// `*_modify_ratio` fields and the level curve of `hard_level_group`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MonsterConfig {
+    pub monster_id: u32,
+    pub monster_template_id: u32,
+    pub monster_name: String,
+    pub monster_introduction: String,
+    pub hard_level_group: u32,
+    pub elite_group: u32,
+    pub attack_modify_ratio: f64,
+    pub defence_modify_ratio: f64,
+    pub hpmodify_ratio: f64,
+    pub speed_modify_ratio: f64,
+    pub stance_modify_ratio: f64,
+    pub speed_modify_value: f64,
+    pub stance_modify_value: f64,
+    /// elements that can break the toughness bar
+    pub weaknesses: Vec<Element>,
+    pub resistances: Vec<ElementResistance>,
+    pub debuff_resistances: Vec<DebuffResistance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+pub struct ElementResistance {
+    pub element: Element,
+    pub value: f64,
+}
+
+/// resistance to a debuff, keyed by its upstream status name e.g.
+/// `STAT_Confine`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct DebuffResistance {
+    pub key: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamMonsterTemplateConfig {
+    #[serde(alias = "MonsterTemplateID")]
+    monster_template_id: u32,
+    #[serde(alias = "TemplateGroupID")]
+    template_group_id: Option<u32>,
+    #[serde(alias = "AttackBase")]
+    attack_base: Param,
+    #[serde(alias = "DefenceBase")]
+    defence_base: Param,
+    #[serde(alias = "HPBase")]
+    hpbase: Param,
+    #[serde(alias = "SpeedBase")]
+    speed_base: Param,
+    #[serde(alias = "StanceBase")]
+    stance_base: Param,
+    #[serde(alias = "CriticalDamageBase")]
+    critical_damage_base: Option<Param>,
+    #[serde(alias = "StatusResistanceBase")]
+    status_resistance_base: Option<Param>,
+}
+
+/// base stats shared by the variants of an enemy
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MonsterTemplateConfig {
+    pub monster_template_id: u32,
+    pub template_group_id: u32,
+    pub attack_base: f64,
+    pub defence_base: f64,
+    pub hpbase: f64,
+    pub speed_base: f64,
+    pub stance_base: f64,
+    pub critical_damage_base: f64,
+    pub status_resistance_base: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpstreamHardLevel {
+    #[serde(alias = "Level")]
+    level: u32,
+    #[serde(alias = "AttackRatio")]
+    attack_ratio: Param,
+    #[serde(alias = "DefenceRatio")]
+    defence_ratio: Option<Param>,
+    #[serde(alias = "HPRatio")]
+    hpratio: Param,
+    #[serde(alias = "SpeedRatio")]
+    speed_ratio: Param,
+    #[serde(alias = "StatusProbability")]
+    status_probability: Option<Param>,
+    #[serde(alias = "StatusResistance")]
+    status_resistance: Option<Param>,
+}
+
+/// stat multipliers of one enemy level
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
+pub struct HardLevel {
+    pub level: u32,
+    pub attack_ratio: f64,
+    pub defence_ratio: f64,
+    pub hpratio: f64,
+    pub speed_ratio: f64,
+    pub status_probability: f64,
+    pub status_resistance: f64,
+}
+
+/// level curve of a `hard_level_group`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct HardLevelGroup {
+    pub group: u32,
+    pub levels: BTreeMap<u32, HardLevel>,
+}
+
+/// an enemy with its stats computed at `level`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Enemy {
+    pub monster_id: u32,
+    pub name: String,
+    pub introduction: String,
+    pub level: u32,
+    /// 1 for common enemies, higher for elites and bosses
+    pub elite_group: u32,
+    pub hp: f64,
+    pub attack: f64,
+    pub defence: f64,
+    pub speed: f64,
+    /// in the upstream unit, not the scale of the in-game bar
+    pub toughness: f64,
+    pub effect_hit_rate: f64,
+    pub effect_res: f64,
+    pub weaknesses: Vec<Element>,
+    pub resistances: Vec<ElementResistance>,
+    pub debuff_resistances: Vec<DebuffResistance>,
+}
+
+impl Enemy {
+    pub fn at_level(
+        monster: &MonsterConfig,
+        template: &MonsterTemplateConfig,
+        curve: &HardLevel,
+    ) -> Self {
+        Self {
+            monster_id: monster.monster_id,
+            name: monster.monster_name.clone(),
+            introduction: monster.monster_introduction.clone(),
+            level: curve.level,
+            elite_group: monster.elite_group,
+            hp: template.hpbase * monster.hpmodify_ratio * curve.hpratio,
+            attack: template.attack_base * monster.attack_modify_ratio * curve.attack_ratio,
+            defence: template.defence_base * monster.defence_modify_ratio * curve.defence_ratio,
+            speed: template.speed_base * monster.speed_modify_ratio * curve.speed_ratio
+                + monster.speed_modify_value,
+            toughness: template.stance_base * monster.stance_modify_ratio
+                + monster.stance_modify_value,
+            effect_hit_rate: curve.status_probability,
+            effect_res: template.status_resistance_base + curve.status_resistance,
+            weaknesses: monster.weaknesses.clone(),
+            resistances: monster.resistances.clone(),
+            debuff_resistances: monster.debuff_resistances.clone(),
+        }
+    }
+
+    /// resistance to `element`, 0 when the enemy has none
+    pub fn resistance(&self, element: Element) -> f64 {
+        self.resistances
+            .iter()
+            .find(|res| res.element == element)
+            .map_or(0.0, |res| res.value)
+    }
+}
+
+#[async_trait]
+impl DbData for MonsterConfig {
+    type TUpstream = HashMap<u32, UpstreamMonsterConfig>;
+    type TLocal = HashMap<u32, MonsterConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/MonsterConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        let text_map = TextMap::read().await?;
+        from.into_iter()
+            .map(|(key, value)| {
+                let monster = MonsterConfig {
+                    monster_id: value.monster_id,
+                    monster_template_id: value.monster_template_id,
+                    monster_name: value.monster_name.read_from_textmap(&text_map)?,
+                    monster_introduction: match value.monster_introduction {
+                        Some(hash) => hash.read_from_textmap(&text_map)?,
+                        None => String::new(),
+                    },
+                    hard_level_group: value.hard_level_group,
+                    elite_group: value.elite_group,
+                    attack_modify_ratio: value.attack_modify_ratio.value,
+                    defence_modify_ratio: value.defence_modify_ratio.value,
+                    hpmodify_ratio: value.hpmodify_ratio.value,
+                    speed_modify_ratio: value.speed_modify_ratio.value,
+                    stance_modify_ratio: value.stance_modify_ratio.value,
+                    speed_modify_value: value.speed_modify_value.map_or(0.0, |v| v.value),
+                    stance_modify_value: value.stance_modify_value.map_or(0.0, |v| v.value),
+                    weaknesses: value.stance_weak_list,
+                    resistances: value
+                        .damage_type_resistance
+                        .into_iter()
+                        .map(|res| ElementResistance {
+                            element: res.damage_type,
+                            value: res.value.value,
+                        })
+                        .collect(),
+                    debuff_resistances: value
+                        .debuff_resist
+                        .into_iter()
+                        .map(|res| DebuffResistance {
+                            key: res.key,
+                            value: res.value.value,
+                        })
+                        .collect(),
+                };
+                Ok((key, monster))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbData for MonsterTemplateConfig {
+    type TUpstream = HashMap<u32, UpstreamMonsterTemplateConfig>;
+    type TLocal = HashMap<u32, MonsterTemplateConfig>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/MonsterTemplateConfig.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(key, value)| {
+                let template = MonsterTemplateConfig {
+                    monster_template_id: value.monster_template_id,
+                    template_group_id: value.template_group_id.unwrap_or(value.monster_template_id),
+                    attack_base: value.attack_base.value,
+                    defence_base: value.defence_base.value,
+                    hpbase: value.hpbase.value,
+                    speed_base: value.speed_base.value,
+                    stance_base: value.stance_base.value,
+                    critical_damage_base: value.critical_damage_base.map_or(0.0, |v| v.value),
+                    status_resistance_base: value.status_resistance_base.map_or(0.0, |v| v.value),
+                };
+                (key, template)
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl DbData for HardLevelGroup {
+    type TUpstream = HashMap<u32, BTreeMap<u32, UpstreamHardLevel>>;
+    type TLocal = HashMap<u32, HardLevelGroup>;
+
+    fn path_data() -> &'static str {
+        "ExcelOutput/HardLevelGroup.json"
+    }
+
+    async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError> {
+        Ok(from
+            .into_iter()
+            .map(|(group, levels)| {
+                let levels = levels
+                    .into_values()
+                    .map(|level| {
+                        let curve = HardLevel {
+                            level: level.level,
+                            attack_ratio: level.attack_ratio.value,
+                            defence_ratio: level.defence_ratio.map_or(1.0, |v| v.value),
+                            hpratio: level.hpratio.value,
+                            speed_ratio: level.speed_ratio.value,
+                            status_probability: level.status_probability.map_or(0.0, |v| v.value),
+                            status_resistance: level.status_resistance.map_or(0.0, |v| v.value),
+                        };
+                        (level.level, curve)
+                    })
+                    .collect();
+                (group, HardLevelGroup { group, levels })
+            })
+            .collect())
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     handler::error::WorkerError,
     routes::honkai::{
         dm_api::{hash::TextHash, types::TextMap},
+        query::Queryable,
         traits::DbData,
     },
 };
@@ -55,6 +56,12 @@ pub struct AvatarPropertyConfig {
     icon_path: String,
 }
 
+impl Queryable for AvatarPropertyConfig {
+    fn name(&self) -> &str {
+        &self.property_name
+    }
+}
+
 #[async_trait]
 impl DbData for AvatarPropertyConfig {
     type TUpstream = HashMap<String, UpstreamAvatarPropertyConfig>;
@@ -1,14 +1,23 @@
 use self::config::AvatarPropertyConfig;
 use crate::{
     handler::error::WorkerError,
-    routes::{endpoint_types::List, honkai::traits::DbData},
+    routes::{
+        endpoint_types::List,
+        honkai::{query::ListQuery, traits::DbData},
+    },
 };
-use axum::Json;
+use axum::{extract::Query, Json};
 
 pub mod config;
 
-pub async fn property() -> Result<Json<List<AvatarPropertyConfig>>, WorkerError> {
-    let property_db = AvatarPropertyConfig::read().await?;
+pub async fn property(
+    Query(query): Query<ListQuery>,
+) -> Result<Json<List<AvatarPropertyConfig>>, WorkerError> {
+    let mut property_db: Vec<(String, AvatarPropertyConfig)> =
+        AvatarPropertyConfig::read().await?.into_iter().collect();
+    property_db.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-    Ok(Json(List::new(property_db.into_values().collect())))
+    Ok(Json(query.apply(
+        property_db.into_iter().map(|(_, value)| value).collect(),
+    )?))
 }
@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     fs::File,
-    io::BufReader,
+    io::{BufReader, ErrorKind},
 };
 
 use async_trait::async_trait;
@@ -9,7 +9,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::{handler::error::WorkerError, routes::honkai::traits::DbData};
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::traits::{splitted_dir, DbData},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpstreamRelicConfig {
@@ -91,7 +94,8 @@ impl RelicConfig {
             .into_iter()
             .collect::<Vec<u32>>();
 
-        std::fs::create_dir_all("/tmp/RelicConfigs")?;
+        let dir = splitted_dir().join("RelicConfigs");
+        std::fs::create_dir_all(&dir)?;
 
         for set_id in set_ids.into_iter() {
             let relics = relic_db
@@ -99,7 +103,7 @@ impl RelicConfig {
                 .filter(|relic| relic.set_id == set_id)
                 .cloned()
                 .collect::<Vec<RelicConfig>>();
-            let filepath = format!("/tmp/RelicConfigs/{}.json", set_id);
+            let filepath = dir.join(format!("{set_id}.json"));
             let json_blob = serde_json::to_string(&relics)?;
             // save to a new file
             std::fs::write(filepath, json_blob)?;
@@ -108,8 +112,13 @@ impl RelicConfig {
     }
 
     pub async fn read_splitted_by_setid(set_id: u32) -> Result<Vec<RelicConfig>, WorkerError> {
-        let filepath = format!("/tmp/RelicConfigs/{}.json", set_id);
-        let file = File::open(filepath)?;
+        let filepath = splitted_dir()
+            .join("RelicConfigs")
+            .join(format!("{set_id}.json"));
+        let file = File::open(filepath).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => WorkerError::NotFound(set_id.to_string()),
+            _ => err.into(),
+        })?;
         let reader = BufReader::new(file);
         let data: Vec<RelicConfig> = serde_json::from_reader(reader)?;
         Ok(data)
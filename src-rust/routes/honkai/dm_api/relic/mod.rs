@@ -7,12 +7,16 @@ use self::{
 };
 use crate::{
     handler::error::WorkerError,
-    routes::{endpoint_types::List, honkai::traits::DbData},
+    routes::{
+        endpoint_types::List,
+        honkai::{alias::resolve_name, query::ListQuery, search::SearchKind, traits::DbData},
+    },
+};
+use axum::{
+    extract::{Path, Query},
+    Json,
 };
-use axum::{extract::Path, Json};
 use futures::future::try_join_all;
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
-use regex::Regex;
 use reqwest::Method;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -34,51 +38,44 @@ pub async fn relic_set(Path(set_id): Path<u32>) -> Result<Json<RelicSetConfig>,
     Ok(Json(data.clone()))
 }
 
-pub async fn relic_set_many() -> Result<Json<List<RelicSetConfig>>, WorkerError> {
-    let relic_set_db = RelicSetConfig::read().await?;
-    Ok(Json(List::new(relic_set_db.into_values().collect())))
+pub async fn relic_set_many(
+    Query(query): Query<ListQuery>,
+) -> Result<Json<List<RelicSetConfig>>, WorkerError> {
+    let mut relic_sets: Vec<RelicSetConfig> = RelicSetConfig::read().await?.into_values().collect();
+    relic_sets.sort_by_key(|set| set.set_id);
+    Ok(Json(query.apply(relic_sets)?))
 }
 
 pub async fn relic_set_search(
     Path(name): Path<String>,
 ) -> Result<Json<Option<RelicSetConfig>>, WorkerError> {
-    // sanitizes params, only interested in chracters
-    let regex = Regex::new("[^a-zA-Z0-9]").unwrap();
-    let matcher = SkimMatcherV2::default();
-
-    let relic_set_db = RelicSetConfig::read().await?;
+    let data = match resolve_name(SearchKind::RelicSet, &name).await? {
+        Some(id) => RelicSetConfig::read().await?.remove(&id),
+        None => None,
+    };
 
-    let relic_name = regex.replace_all(&name, "").to_string();
-    let data: Vec<RelicSetConfig> = relic_set_db
-        .into_values()
-        .filter(|v| {
-            let fuzz_result = matcher.fuzzy_match(&v.set_name, &relic_name);
-            fuzz_result.is_some()
-        })
-        .collect();
-    if data.is_empty() {
-        return Ok(Json(None));
-    }
-    Ok(Json(data.get(0).cloned()))
+    Ok(Json(data))
 }
 
 pub async fn relics_by_set(
     Path(set_id): Path<u32>,
+    Query(query): Query<ListQuery>,
 ) -> Result<Json<List<RelicConfig>>, WorkerError> {
     let relic_db = RelicConfig::read_splitted_by_setid(set_id).await?;
 
-    let data = relic_db
+    let mut data: Vec<RelicConfig> = relic_db
         .into_iter()
         .filter(|value| value.set_id == set_id)
         .collect();
+    data.sort_by_key(|relic| relic.id);
 
-    Ok(Json(List::new(data)))
+    Ok(Json(query.apply(data)?))
 }
 
 pub async fn relics_by_set_post(
     Json(params): Json<List<u32>>,
 ) -> Result<Json<List<RelicConfig>>, WorkerError> {
-    let List { list: set_ids } = params;
+    let List { list: set_ids, .. } = params;
     println!("should see {:?}", set_ids);
     let data = try_join_all(
         set_ids
@@ -133,7 +130,7 @@ pub async fn set_bonus_many(
 ) -> Result<Json<List<RelicSetSkillConfig>>, WorkerError> {
     let bonus_db = RelicSetSkillConfig::read().await?;
     let ids = match (&method, relic_ids) {
-        (&Method::POST, Some(Json(List { list }))) => Some(list),
+        (&Method::POST, Some(Json(List { list, .. }))) => Some(list),
         _ => None,
     };
 
@@ -148,10 +145,10 @@ pub async fn set_bonus_many(
 pub async fn relic_slot_type(
     set_ids: Json<List<u32>>,
 ) -> Result<Json<HashMap<u32, RelicType>>, WorkerError> {
-    let Json(List { list: set_ids }) = set_ids;
+    let Json(List { list: set_ids, .. }) = set_ids;
     let mut ret: HashMap<u32, RelicType> = HashMap::new();
     for set_id in set_ids {
-        let db = RelicConfig::read_splitted_by_setid(set_id).await.unwrap();
+        let db = RelicConfig::read_splitted_by_setid(set_id).await?;
         db.iter().for_each(|relic_cfg| {
             ret.insert(relic_cfg.id, relic_cfg.ttype);
         })
@@ -6,7 +6,7 @@ use crate::{
             hash::HashedString,
             types::{Param, Property, TextMap},
         },
-        traits::DbData,
+        traits::{skip_bad_record, DbData},
     },
 };
 use async_trait::async_trait;
@@ -89,13 +89,13 @@ impl DbData for RelicSetSkillConfig {
                     .map(|cfg| {
                         let params = cfg.ability_param_list.iter().map(|e| e.value).collect();
                         let desc_dehashed = cfg.skill_desc.dehash(&text_map).unwrap_or_default();
-                        let current_param: Vec<String> = get_sorted_params(params, &desc_dehashed)
+                        let current_param: Vec<String> = get_sorted_params(params, &desc_dehashed)?
                             .iter()
                             .map(|e| e.to_string())
                             .collect();
-                        current_param
+                        Ok(current_param)
                     })
-                    .collect();
+                    .collect::<Result<Vec<Vec<String>>, WorkerError>>()?;
 
                 let value = RelicSetSkillConfig {
                     set_id: key,
@@ -109,24 +109,30 @@ impl DbData for RelicSetSkillConfig {
                             for prop in &e.property_list {
                                 let mut relic = RelicParam::default();
                                 for val in prop.values() {
-                                    match val.is_string() {
-                                        true => {
-                                            relic.property =
-                                                Property::from_str(val.as_str().unwrap()).unwrap()
+                                    match val.as_str() {
+                                        Some(property) => {
+                                            relic.property = Property::from_str(property)?
+                                        }
+                                        None => {
+                                            relic.value = val["Value"].as_f64().ok_or(
+                                                WorkerError::UpstreamData(format!(
+                                                    "relic set {key} has a non-numeric property value"
+                                                )),
+                                            )?
                                         }
-                                        false => relic.value = val["Value"].as_f64().unwrap(),
                                     }
                                 }
                                 ret.push(relic)
                             }
-                            ret
+                            Ok(ret)
                         })
-                        .collect(),
+                        .collect::<Result<Vec<Vec<RelicParam>>, WorkerError>>()?,
                     ability_name: vec![],
                     ability_param_list: param_as_string,
                 };
-                (key, value)
+                Ok((key, value))
             })
+            .filter_map(|record| skip_bad_record(Self::path_data(), record))
             .collect();
 
         Ok(transformed)
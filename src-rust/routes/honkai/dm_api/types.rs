@@ -1,4 +1,4 @@
-use crate::builder::{get_db_client, traits::DbAction};
+use crate::builder::traits::DbAction;
 use crate::handler::{error::WorkerError, FromAxumResponse};
 use async_trait::async_trait;
 use axum::Json;
@@ -91,8 +91,11 @@ pub enum SkillType {
 
 #[async_trait]
 impl DbAction for SkillType {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_skillType"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = SkillType::iter()
             .enumerate()
             .map(|(index, ttype)| {
@@ -102,14 +105,7 @@ impl DbAction for SkillType {
                 )
             })
             .collect();
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_skillType").await?;
-        Ok(())
+        Ok(st)
     }
 }
 
@@ -124,6 +120,8 @@ pub struct AssetPath(pub String);
     JsonResponse,
     Clone,
     Copy,
+    PartialEq,
+    Eq,
     JsonSchema,
     EnumString,
     Dummy,
@@ -149,6 +147,8 @@ pub enum Element {
     JsonResponse,
     Clone,
     Copy,
+    PartialEq,
+    Eq,
     JsonSchema,
     EnumString,
     EnumIter,
@@ -248,8 +248,11 @@ impl Element {
 
 #[async_trait]
 impl DbAction for Element {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_element"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = Element::iter()
             .map(|element| {
                 Statement::with_args(
@@ -259,20 +262,17 @@ impl DbAction for Element {
             })
             .collect();
 
-        client.batch(st).await?;
-        Ok(())
-    }
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_element").await?;
-        Ok(())
+        Ok(st)
     }
 }
 
 #[async_trait]
 impl DbAction for Path {
-    async fn seed() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
+    fn tables() -> &'static [&'static str] {
+        &["honkai_path"]
+    }
+
+    async fn seed_statements() -> Result<Vec<Statement>, WorkerError> {
         let st: Vec<Statement> = Path::iter()
             .map(|path| {
                 Statement::with_args(
@@ -282,13 +282,6 @@ impl DbAction for Path {
             })
             .collect();
 
-        client.batch(st).await?;
-        Ok(())
-    }
-
-    async fn teardown() -> Result<(), WorkerError> {
-        let client = get_db_client().await?;
-        client.execute("DELETE FROM honkai_path").await?;
-        Ok(())
+        Ok(st)
     }
 }
@@ -1,8 +1,9 @@
 use self::types::{EstimateCfg, JadeEstimateResponse, RewardFrequency, RewardSource};
-use crate::handler::error::WorkerError;
+use super::dm_api::challenge::schedule::ChallengeCycle;
+use crate::handler::error::{ComputationType, WorkerError};
 use axum::{extract::rejection::JsonRejection, Json};
 use chrono::Utc;
-use tracing::{error, instrument};
+use tracing::{error, instrument, warn};
 
 #[cfg(test)]
 mod tests;
@@ -14,7 +15,14 @@ pub async fn handle(
     rpayload: Result<Json<EstimateCfg>, JsonRejection>,
 ) -> Result<Json<JadeEstimateResponse>, WorkerError> {
     if let Ok(Json(payload)) = rpayload {
-        let rewards = RewardSource::compile_sources(&payload)?;
+        let cycle = match ChallengeCycle::load(&payload.server).await {
+            Ok(cycle) => cycle,
+            Err(err) => {
+                warn!("MoC schedule unavailable, using the launch cycle: {err}");
+                ChallengeCycle::launch(&payload.server)
+            }
+        };
+        let rewards = RewardSource::compile_sources(&payload, &cycle)?;
         // let (diff_days, _) = get_date_differences(&payload.server, payload.get_until_date());
         let diff_days = RewardFrequency::Daily.get_difference(
             Utc::now(),
@@ -36,7 +44,9 @@ pub async fn handle(
         let response = JadeEstimateResponse {
             total_jades,
             rolls: total_rolls,
-            days: diff_days.try_into().unwrap(),
+            days: diff_days
+                .try_into()
+                .map_err(|_| WorkerError::Computation(ComputationType::BadNumberCast))?,
             sources: rewards,
         };
 
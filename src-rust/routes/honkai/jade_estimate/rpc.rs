@@ -54,17 +54,25 @@ impl TryFrom<jadeestimate::JadeEstimateCfg> for EstimateCfg {
         } = value.battle_pass.ok_or(WorkerError::EmptyBody)?;
 
         let data = EstimateCfg {
-            server: value.server.try_into().unwrap(),
+            server: value
+                .server
+                .try_into()
+                .map_err(|_| WorkerError::ParseData(format!("unknown server {}", value.server)))?,
             until_date: super::types::SimpleDate { day, month, year },
             rail_pass: super::types::RailPassCfg {
                 use_rail_pass,
                 days_left,
             },
             battle_pass: super::types::BattlePassOption {
-                battle_pass_type: battle_pass_type.try_into().unwrap(),
+                battle_pass_type: battle_pass_type.try_into().map_err(|_| {
+                    WorkerError::ParseData(format!("unknown battle pass type {battle_pass_type}"))
+                })?,
                 current_level,
             },
-            eq: value.eq.try_into().unwrap(),
+            eq: value
+                .eq
+                .try_into()
+                .map_err(|_| WorkerError::ParseData(format!("unknown eq tier {}", value.eq)))?,
             moc: value.moc,
             moc_current_week_done: value.moc_current_week_done,
             current_rolls: value.current_rolls,
@@ -1,5 +1,28 @@
-use super::types::{RewardFrequency, Server};
-use chrono::{TimeZone, Utc};
+use super::types::{today_at_reset, RewardFrequency, Server};
+use crate::routes::honkai::dm_api::challenge::{
+    schedule::ChallengeCycle, types::ScheduleDataChallengeMaze,
+};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+/// two week windows from 06-12, like the launch cycle
+fn moc_cycle(server: &Server) -> ChallengeCycle {
+    let window = |id, day| {
+        let begin = NaiveDate::from_ymd_opt(2023, 6, day).unwrap();
+        ScheduleDataChallengeMaze {
+            id,
+            begin_time: begin.and_hms_opt(4, 0, 0).unwrap(),
+            end_time: (begin + Duration::weeks(2)).and_hms_opt(4, 0, 0).unwrap(),
+        }
+    };
+    ChallengeCycle::from_windows(&[window(1, 12), window(2, 26)], server).unwrap()
+}
+
+/// resets of `moc_cycle`, counted from the reset before `from_date` like
+/// `src_moc` does
+fn moc_resets(from_date: DateTime<Utc>, to_date: DateTime<Utc>, server: &Server) -> u32 {
+    let from_date = today_at_reset(&from_date, server);
+    RewardFrequency::get_biweek_diff(from_date, to_date, &moc_cycle(server)).unwrap()
+}
 
 // TODO: we really need to thoroughly unit test all date diffing functions
 // TODO: NA server
@@ -27,15 +50,11 @@ fn week_diffing() {
 fn biweek_diffing_asia() {
     let from_date = Utc.with_ymd_and_hms(2023, 6, 11, 2, 12, 12).unwrap();
     let to_date = Utc.with_ymd_and_hms(2023, 6, 30, 18, 29, 27).unwrap();
-    let biweeks = RewardFrequency::BiWeekly
-        .get_difference(from_date, to_date, &Server::Asia)
-        .unwrap();
+    let biweeks = moc_resets(from_date, to_date, &Server::Asia);
     assert_eq!(biweeks, 2);
 
     let from_date = Utc.with_ymd_and_hms(2023, 6, 19, 19, 12, 12).unwrap();
-    let biweeks = RewardFrequency::BiWeekly
-        .get_difference(from_date, to_date, &Server::Asia)
-        .unwrap();
+    let biweeks = moc_resets(from_date, to_date, &Server::Asia);
     assert_eq!(biweeks, 1);
 }
 
@@ -43,23 +62,17 @@ fn biweek_diffing_asia() {
 fn biweek_diffing_na() {
     let from_date = Utc.with_ymd_and_hms(2023, 6, 12, 1, 12, 12).unwrap();
     let to_date = Utc.with_ymd_and_hms(2023, 6, 12, 18, 29, 27).unwrap();
-    let biweeks = RewardFrequency::BiWeekly
-        .get_difference(from_date, to_date, &Server::America)
-        .unwrap();
+    let biweeks = moc_resets(from_date, to_date, &Server::America);
     assert_eq!(biweeks, 1);
 
     let from_date = Utc.with_ymd_and_hms(2023, 6, 13, 14, 12, 12).unwrap();
     let to_date = Utc.with_ymd_and_hms(2023, 6, 13, 18, 29, 27).unwrap();
-    let biweeks = RewardFrequency::BiWeekly
-        .get_difference(from_date, to_date, &Server::America)
-        .unwrap();
+    let biweeks = moc_resets(from_date, to_date, &Server::America);
     assert_eq!(biweeks, 0);
 
     let from_date = Utc.with_ymd_and_hms(2023, 6, 12, 1, 12, 12).unwrap();
     let to_date = Utc.with_ymd_and_hms(2023, 6, 26, 18, 29, 27).unwrap();
-    let biweeks = RewardFrequency::BiWeekly
-        .get_difference(from_date, to_date, &Server::America)
-        .unwrap();
+    let biweeks = moc_resets(from_date, to_date, &Server::America);
     assert_eq!(biweeks, 2);
 }
 
@@ -102,3 +115,61 @@ fn whole_patch_diffing() {
         .unwrap();
     assert_eq!(diff, 2);
 }
+
+/// feeds random protobuf payloads into the `TryFrom` conversion, bad enum
+/// values or missing messages should come back as errors instead of panics
+#[test]
+fn rpc_conversion_random_payloads() {
+    use super::rpc::jadeestimate;
+    use super::types::EstimateCfg;
+    use crate::handler::error::WorkerError;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    // skewed towards small values so both in-range and out-of-range enums
+    // are covered
+    let enum_value = |rng: &mut StdRng| match rng.gen_bool(0.7) {
+        true => rng.gen_range(-2..10),
+        false => rng.gen(),
+    };
+
+    for _ in 0..2000 {
+        let server = enum_value(&mut rng);
+        let eq = enum_value(&mut rng);
+        let battle_pass_type = enum_value(&mut rng);
+        let payload = jadeestimate::JadeEstimateCfg {
+            server,
+            until_date: rng.gen_bool(0.9).then(|| jadeestimate::SimpleDate {
+                day: rng.gen(),
+                month: rng.gen(),
+                year: rng.gen(),
+            }),
+            rail_pass: rng.gen_bool(0.9).then(|| jadeestimate::RailPassCfg {
+                use_rail_pass: rng.gen(),
+                days_left: rng.gen(),
+            }),
+            battle_pass: rng.gen_bool(0.9).then(|| jadeestimate::BattlePassOption {
+                battle_pass_type,
+                current_level: rng.gen(),
+            }),
+            eq,
+            moc: rng.gen(),
+            moc_current_week_done: rng.gen(),
+            current_rolls: rng.gen(),
+            current_jades: rng.gen(),
+            daily_refills: rng.gen(),
+        };
+        let has_messages = payload.until_date.is_some()
+            && payload.rail_pass.is_some()
+            && payload.battle_pass.is_some();
+        let valid_enums =
+            (0..=2).contains(&server) && (0..=6).contains(&eq) && (0..=2).contains(&battle_pass_type);
+
+        let converted: Result<EstimateCfg, WorkerError> = payload.try_into();
+        match (has_messages, valid_enums) {
+            (true, true) => assert!(converted.is_ok()),
+            (false, _) => assert!(matches!(converted, Err(WorkerError::EmptyBody))),
+            (true, false) => assert!(matches!(converted, Err(WorkerError::ParseData(_)))),
+        }
+    }
+}
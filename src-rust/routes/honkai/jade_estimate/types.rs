@@ -3,7 +3,10 @@ use crate::{
         error::{ComputationType, WorkerError},
         FromAxumResponse,
     },
-    routes::honkai::{patch::types::Patch, utils::helpers::get_next_monday},
+    routes::honkai::{
+        dm_api::challenge::schedule::ChallengeCycle, patch::types::Patch,
+        utils::helpers::get_next_monday,
+    },
 };
 use axum::Json;
 use chrono::{DateTime, Datelike, Duration, TimeZone, Utc, Weekday};
@@ -144,6 +147,8 @@ pub enum RewardFrequency {
 }
 
 impl RewardFrequency {
+    /// `BiWeekly` counts launch cycle resets, use `get_biweek_diff` with the
+    /// loaded MoC schedule instead
     pub fn get_difference(
         &self,
         from_date: DateTime<Utc>,
@@ -156,7 +161,9 @@ impl RewardFrequency {
         match self {
             RewardFrequency::Daily => Self::get_day_diff(from_date, to_date),
             RewardFrequency::Weekly => Self::get_week_diff(from_date, to_date, server),
-            RewardFrequency::BiWeekly => Self::get_biweek_diff(from_date, to_date, server),
+            RewardFrequency::BiWeekly => {
+                Self::get_biweek_diff(from_date, to_date, &ChallengeCycle::launch(server))
+            }
             RewardFrequency::Monthly => Self::get_month_diff(from_date, to_date),
             RewardFrequency::HalfPatch => Patch::half_patch_passed_diff(from_date, to_date),
             RewardFrequency::WholePatch => Patch::patch_passed_diff(from_date, to_date),
@@ -201,45 +208,16 @@ impl RewardFrequency {
 
     /// Counts number of biweekly reset (MoC etc.) that has passed between 2
     /// dates
-    /// NOTE: this is counted from the start of the game's launch, not from
-    /// `from_date`
-    fn get_biweek_diff(
+    /// NOTE: this is counted from the resets of `cycle`, not from `from_date`
+    pub fn get_biweek_diff(
         from_date: DateTime<Utc>,
         to_date: DateTime<Utc>,
-        server: &Server,
+        cycle: &ChallengeCycle,
     ) -> Result<u32, WorkerError> {
         if from_date > to_date {
             return Err(WorkerError::Computation(ComputationType::BadDateComparison));
         }
-        let base_moc = Utc
-            .with_ymd_and_hms(2023, 5, 29, Server::get_utc_reset_hour(server), 0, 0)
-            .unwrap();
-
-        let mut start = base_moc;
-        while start < from_date {
-            start += Duration::weeks(2);
-        }
-        let mut end = base_moc;
-        while end < to_date {
-            if end + Duration::weeks(2) > to_date {
-                break;
-            }
-            end += Duration::weeks(2);
-        }
-
-        let mut diff_biweeks = 0;
-        // this flag is to filter out every other monday that's not the start
-        // of a new moc cycle
-        let mut moc_start = true;
-        for day in DateRange(start, end + Duration::days(1)) {
-            if day.weekday() == Weekday::Mon {
-                if moc_start {
-                    diff_biweeks += 1;
-                }
-                moc_start = !moc_start;
-            }
-        }
-        Ok(diff_biweeks)
+        Ok(cycle.resets_between(from_date, to_date))
     }
 
     pub fn get_month_diff(
@@ -336,7 +314,11 @@ pub fn today_at_reset(a: &DateTime<Utc>, server: &Server) -> DateTime<Utc> {
 }
 
 impl RewardSource {
-    pub fn compile_sources(cfg: &EstimateCfg) -> Result<Vec<Self>, WorkerError> {
+    /// `cycle` is the MoC schedule of `cfg.server`
+    pub fn compile_sources(
+        cfg: &EstimateCfg,
+        cycle: &ChallengeCycle,
+    ) -> Result<Vec<Self>, WorkerError> {
         let dt_to = cfg.get_until_date();
         let diff_days = RewardFrequency::Daily.get_difference(Utc::now(), dt_to, &cfg.server)?;
 
@@ -346,7 +328,13 @@ impl RewardSource {
         let src_daily_mission = Self::src_daily_mission(diff_days);
         let src_daily_text = Self::src_daily_text(diff_days);
         let src_hoyolab_checkin = Self::src_hoyolab_checkin(dt_to);
-        let src_moc = Self::src_moc(cfg.moc, cfg.moc_current_week_done, dt_to, &cfg.server)?;
+        let src_moc = Self::src_moc(
+            cfg.moc,
+            cfg.moc_current_week_done,
+            dt_to,
+            &cfg.server,
+            cycle,
+        )?;
         let src_char_trial = Self::src_char_trial(dt_to, &cfg.server)?;
         let src_ember_trade = Self::src_ember_trade(dt_to, &cfg.server)?;
         let mut sources = vec![
@@ -563,9 +551,10 @@ impl RewardSource {
         done_this_week: bool,
         until_date: DateTime<Utc>,
         server: &Server,
+        cycle: &ChallengeCycle,
     ) -> Result<Self, WorkerError> {
-        let freq = RewardFrequency::BiWeekly;
-        let mut diffs = freq.get_difference(Utc::now(), until_date, server)?;
+        let from_date = today_at_reset(&Utc::now(), server);
+        let mut diffs = RewardFrequency::get_biweek_diff(from_date, until_date, cycle)?;
         if !done_this_week {
             diffs += 1;
         }
@@ -619,13 +608,17 @@ mod tests {
     use chrono::{Duration, Utc};
 
     use super::{RewardFrequency, Server};
+    use crate::routes::honkai::dm_api::challenge::schedule::ChallengeCycle;
 
     #[test]
     fn biweek() {
         let from = Utc::now();
-        let diff_biweeks =
-            RewardFrequency::get_biweek_diff(from, from + Duration::days(39), &Server::America)
-                .unwrap();
+        let diff_biweeks = RewardFrequency::get_biweek_diff(
+            from,
+            from + Duration::days(39),
+            &ChallengeCycle::launch(&Server::America),
+        )
+        .unwrap();
         println!("{}", diff_biweeks);
     }
 }
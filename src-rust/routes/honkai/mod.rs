@@ -1,11 +1,21 @@
+pub mod alias;
 pub mod banner;
+pub mod changelog;
+pub mod combat_log;
 pub mod dm_api;
 pub mod jade_estimate;
 pub mod patch;
+pub mod planner;
 pub mod probability_rate;
+pub mod query;
+pub mod repository;
+pub mod search;
+pub mod simulator;
+pub mod toughness;
 pub mod traits;
 pub mod utils;
 
+use self::dm_api::challenge::{challenge, challenge_current, challenge_list};
 use self::dm_api::character::{character_by_name, character_many, eidolon, promotion};
 use self::dm_api::equipment::stat_ranking::stat_ranking;
 use self::dm_api::equipment::{
@@ -13,16 +23,23 @@ use self::dm_api::equipment::{
 };
 use self::dm_api::equipment_skill::trace;
 use self::dm_api::item::item_list;
+use self::dm_api::monster::{enemy, enemy_list, enemy_search};
 use self::dm_api::property::property;
 use self::dm_api::relic::{
     mainstat_spread, relic_set, relic_set_many, relic_set_search, relic_slot_type, relics_by_set,
     relics_by_set_post, set_bonus, set_bonus_many, substat_spread,
 };
 use self::dm_api::{atlas, character, character_skill};
+use crate::builder::config::env_config;
 use axum::routing::{get, post};
 use axum::Router;
 
 pub fn honkai_routes() -> Router {
+    // runtime aliases are opt in, the endpoint has no auth of its own
+    let alias = match env_config().server.alias_extend {
+        true => get(alias::alias_list).post(alias::alias_extend),
+        false => get(alias::alias_list),
+    };
     Router::new()
         .route("/jade_estimate", post(jade_estimate::handle))
         .route("/probability_rate", post(probability_rate::handle))
@@ -62,4 +79,23 @@ pub fn honkai_routes() -> Router {
         .route("/relic_set/:id", get(relic_set))
         .route("/relic_set/search/:name", get(relic_set_search))
         .route("/item", get(item_list).post(item_list))
+        .route("/enemy", get(enemy_list))
+        .route("/enemy/:id", get(enemy))
+        .route("/enemy/search/:name", get(enemy_search))
+        .route("/moc", get(challenge_list))
+        .route("/moc/current", get(challenge_current))
+        .route("/moc/:id", get(challenge))
+        .route("/planner/avatar", post(planner::avatar_planner))
+        .route("/planner/light_cone", post(planner::light_cone_planner))
+        .route("/planner/team", post(planner::team_planner))
+        .route(
+            "/planner/inventory",
+            post(planner::inventory::inventory_planner),
+        )
+        .route("/search", get(search::search))
+        .route("/simulator", post(simulator::av_simulator))
+        .route("/break", post(toughness::team_break))
+        .route("/alias", alias)
+        .route("/changelog", get(changelog::changelog))
+        .route("/combat_log", post(combat_log::mvp_analysis))
 }
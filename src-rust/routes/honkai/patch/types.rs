@@ -1,7 +1,9 @@
 use crate::{
     handler::error::{ComputationType, WorkerError},
     routes::honkai::{
+        alias::AliasRegistry,
         dm_api::{character::types::AvatarConfig, types::SkillType},
+        search::SearchKind,
         traits::DbData,
     },
 };
@@ -74,20 +76,13 @@ impl PatchBanner {
                 None => (None, None),
             };
 
-            let fk1 = character_list
-                .iter()
-                .find(|(_, e)| char1.is_some() && e.avatar_votag.eq(char1.unwrap()));
-            let fk2 = character_list
-                .iter()
-                .find(|(_, e)| char2.is_some() && e.avatar_votag.eq(char2.unwrap()));
-
-            let char_data1 = match fk1 {
-                Some((key, _)) => character_list.get(key).cloned(),
-                None => None,
-            };
-            let char_data2 = match fk2 {
-                Some((key, _)) => character_list.get(key).cloned(),
-                None => None,
+            let (char_data1, char_data2) = {
+                let registry = AliasRegistry::global().await?;
+                let resolve = |name: Option<&str>| {
+                    name.and_then(|name| registry.resolve(SearchKind::Character, name))
+                        .and_then(|id| character_list.get(&id).cloned())
+                };
+                (resolve(char1), resolve(char2))
             };
             banners.push(PatchBanner {
                 character_data: char_data1,
@@ -0,0 +1,200 @@
+use super::{
+    avatar_materials, light_cone_materials, merge,
+    types::{CraftStep, FarmStep, InventoryRequest, Materials, Shortfall},
+    CREDIT_ID,
+};
+use crate::{
+    handler::error::WorkerError,
+    routes::honkai::{
+        dm_api::item::{compose::ItemComposeConfig, types::Item},
+        repository::repository,
+        traits::DbData,
+    },
+};
+use axum::Json;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// crafts and farm counts that cover `need` on top of `inventory`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Resolution {
+    /// `(item_id, count, credits)` in crafting order
+    pub craft: Vec<(u32, u64, u64)>,
+    pub farm: Materials,
+}
+
+pub async fn inventory_planner(
+    Json(request): Json<InventoryRequest>,
+) -> Result<Json<Shortfall>, WorkerError> {
+    let mut need = Materials::new();
+    for plan in request.plans.iter() {
+        merge(&mut need, avatar_materials(plan).await?.1);
+    }
+    for light_cone in request.light_cones.iter() {
+        merge(&mut need, light_cone_materials(light_cone).await?.1);
+    }
+    let recipes: Vec<ItemComposeConfig> = ItemComposeConfig::read().await?.into_values().collect();
+    let resolution = resolve(&need, &request.inventory, &recipes);
+
+    let ids: Vec<u32> = resolution
+        .craft
+        .iter()
+        .map(|(item_id, _, _)| *item_id)
+        .chain(resolution.farm.keys().copied())
+        .collect();
+    let items: BTreeMap<u32, Item> = repository::<Item>()
+        .await?
+        .list(Some(&ids))
+        .await?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let item = |item_id: u32| {
+        items
+            .get(&item_id)
+            .ok_or_else(|| WorkerError::NotFound(format!("item {item_id}")))
+    };
+
+    let craft = resolution
+        .craft
+        .iter()
+        .map(|(item_id, count, credits)| {
+            Ok(CraftStep {
+                item_id: *item_id,
+                name: item(*item_id)?.item_name.clone(),
+                count: *count,
+                credits: *credits,
+            })
+        })
+        .collect::<Result<Vec<CraftStep>, WorkerError>>()?;
+    let farm = resolution
+        .farm
+        .iter()
+        .map(|(item_id, count)| {
+            let item = item(*item_id)?;
+            let drops = request
+                .drops_per_run
+                .get(item_id)
+                .copied()
+                .or_else(|| default_drops(item));
+            Ok(FarmStep {
+                item_id: *item_id,
+                name: item.item_name.clone(),
+                count: *count,
+                runs: drops
+                    .filter(|drops| *drops > 0.0)
+                    .map(|drops| (*count as f64 / drops).ceil() as u64),
+            })
+        })
+        .collect::<Result<Vec<FarmStep>, WorkerError>>()?;
+
+    Ok(Json(Shortfall { craft, farm }))
+}
+
+/// average drops of one run at the highest equilibrium level
+fn default_drops(item: &Item) -> Option<f64> {
+    if item.id == CREDIT_ID {
+        return None;
+    }
+    match item.rarity.stars() {
+        1 | 2 => Some(5.0),
+        3 => Some(3.0),
+        4 => Some(1.5),
+        5 => Some(0.5),
+        _ => None,
+    }
+}
+
+/// walks the synthesis chains from the top tier down, every missing item
+/// with a recipe is crafted and its inputs are added to what's needed, the
+/// rest is farmed
+pub fn resolve(
+    need: &Materials,
+    inventory: &Materials,
+    recipes: &[ItemComposeConfig],
+) -> Resolution {
+    let mut by_result: BTreeMap<u32, &ItemComposeConfig> = BTreeMap::new();
+    for recipe in recipes
+        .iter()
+        .filter(|recipe| !recipe.material_cost.is_empty())
+    {
+        by_result.entry(recipe.item_id).or_insert(recipe);
+    }
+
+    // crafting costs credits even when none were needed before
+    let mut tiers: BTreeMap<u32, u32> = BTreeMap::from([(CREDIT_ID, 0)]);
+    let mut pending: Vec<u32> = need.keys().copied().collect();
+    while let Some(item_id) = pending.pop() {
+        if tiers.contains_key(&item_id) {
+            continue;
+        }
+        tiers.insert(item_id, tier(item_id, &by_result, &mut BTreeSet::new()));
+        if let Some(recipe) = by_result.get(&item_id) {
+            pending.extend(recipe.material_cost.iter().map(|cost| cost.item_id));
+        }
+    }
+    // a recipe whose inputs aren't below its result (a cycle or a sideways
+    // trade) would need its inputs after they were settled
+    by_result.retain(|item_id, recipe| {
+        recipe
+            .material_cost
+            .iter()
+            .all(|cost| tiers.get(&cost.item_id) < tiers.get(item_id))
+    });
+
+    let mut order: Vec<u32> = tiers.keys().copied().collect();
+    order.sort_by_key(|item_id| (*item_id == CREDIT_ID, Reverse(tiers[item_id]), *item_id));
+
+    let mut need = need.clone();
+    let mut resolution = Resolution::default();
+    for item_id in order {
+        let wanted = need.get(&item_id).copied().unwrap_or_default();
+        let owned = inventory.get(&item_id).copied().unwrap_or_default();
+        let missing = wanted.saturating_sub(owned);
+        if missing == 0 {
+            continue;
+        }
+        match by_result.get(&item_id) {
+            Some(recipe) => {
+                for cost in recipe.material_cost.iter() {
+                    *need.entry(cost.item_id).or_default() += missing * cost.item_num as u64;
+                }
+                let credits = missing * recipe.coin_cost as u64;
+                if credits > 0 {
+                    *need.entry(CREDIT_ID).or_default() += credits;
+                }
+                resolution.craft.push((item_id, missing, credits));
+            }
+            None => {
+                resolution.farm.insert(item_id, missing);
+            }
+        }
+    }
+    // settled top down, crafted bottom up
+    resolution.craft.reverse();
+    resolution
+}
+
+/// synthesis steps between `item_id` and its farmable base material
+fn tier(
+    item_id: u32,
+    by_result: &BTreeMap<u32, &ItemComposeConfig>,
+    visiting: &mut BTreeSet<u32>,
+) -> u32 {
+    let Some(recipe) = by_result.get(&item_id) else {
+        return 0;
+    };
+    if !visiting.insert(item_id) {
+        return 0;
+    }
+    let tier = recipe
+        .material_cost
+        .iter()
+        .map(|cost| tier(cost.item_id, by_result, visiting) + 1)
+        .max()
+        .unwrap_or_default();
+    visiting.remove(&item_id);
+    tier
+}
@@ -0,0 +1,315 @@
+use self::types::{
+    AvatarPlan, AvatarPlanRequest, ExpMaterial, LightConePlan, LightConePlanRequest, MaterialCount,
+    MaterialGroup, Materials, TeamPlan, TeamPlanRequest,
+};
+use super::{
+    dm_api::{
+        character::{
+            exp_config::{AvatarExpItemConfig, ExpType},
+            promotion_config::AvatarPromotionConfig,
+            types::{AvatarConfig, MiniItem},
+        },
+        character_skill::types::AvatarSkillTreeConfig,
+        equipment::{
+            equipment_config::EquipmentConfig,
+            equipment_promotion_config::EquipmentPromotionConfig,
+            exp_config::{EquipmentExpItemConfig, EquipmentExpType},
+        },
+        item::types::Item,
+    },
+    repository::repository,
+    traits::DbData,
+};
+use crate::handler::error::WorkerError;
+use axum::Json;
+use std::collections::BTreeMap;
+
+pub mod inventory;
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// item id of credits
+pub const CREDIT_ID: u32 = 2;
+
+/// credits spent per point of EXP fed to a character
+const CREDITS_PER_EXP: f64 = 0.1;
+
+pub async fn avatar_planner(
+    Json(request): Json<AvatarPlanRequest>,
+) -> Result<Json<AvatarPlan>, WorkerError> {
+    let (exp, materials) = avatar_materials(&request).await?;
+
+    let ids: Vec<u32> = materials.keys().copied().collect();
+    let items = repository::<Item>().await?.list(Some(&ids)).await?;
+    Ok(Json(AvatarPlan {
+        avatar_id: request.avatar_id,
+        exp,
+        groups: group_materials(&materials, items)?,
+    }))
+}
+
+/// character EXP and every material of one planner row
+pub async fn avatar_materials(
+    request: &AvatarPlanRequest,
+) -> Result<(u64, Materials), WorkerError> {
+    let avatar_id = request.avatar_id;
+    let not_found = || WorkerError::NotFound(avatar_id.to_string());
+
+    let avatar = repository::<AvatarConfig>()
+        .await?
+        .get(avatar_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let promotion = AvatarPromotionConfig::read()
+        .await?
+        .remove(&avatar_id)
+        .ok_or_else(not_found)?;
+    let exp_type = ExpType::read()
+        .await?
+        .remove(&avatar.exp_group)
+        .ok_or_else(|| WorkerError::NotFound(format!("exp group {}", avatar.exp_group)))?;
+    let exp_items: Vec<ExpMaterial> = AvatarExpItemConfig::read()
+        .await?
+        .into_values()
+        .map(|item| ExpMaterial {
+            item_id: item.item_id,
+            exp: item.exp,
+            credits: (item.exp as f64 * CREDITS_PER_EXP) as u32,
+        })
+        .collect();
+    let traces: BTreeMap<u32, AvatarSkillTreeConfig> = AvatarSkillTreeConfig::read()
+        .await?
+        .into_iter()
+        .filter(|(_, trace)| trace.avatar_id == avatar_id)
+        .collect();
+
+    let mut materials = Materials::new();
+    let exp = exp_type.exp_between(request.current.level, request.target.level);
+    add_ascension(
+        (request.current.level, request.current.ascension),
+        (request.target.level, request.target.ascension),
+        &promotion.max_level,
+        &promotion.promotion_cost_list,
+        &mut materials,
+    )?;
+    add_exp(exp, &exp_items, &mut materials);
+    add_traces(request, &traces, &mut materials)?;
+    Ok((exp, materials))
+}
+
+pub async fn light_cone_planner(
+    Json(request): Json<LightConePlanRequest>,
+) -> Result<Json<LightConePlan>, WorkerError> {
+    let (exp, materials) = light_cone_materials(&request).await?;
+
+    let ids: Vec<u32> = materials.keys().copied().collect();
+    let items = repository::<Item>().await?.list(Some(&ids)).await?;
+    Ok(Json(LightConePlan {
+        equipment_id: request.equipment_id,
+        exp,
+        groups: group_materials(&materials, items)?,
+    }))
+}
+
+/// light cone EXP and every material of one light cone upgrade
+pub async fn light_cone_materials(
+    request: &LightConePlanRequest,
+) -> Result<(u64, Materials), WorkerError> {
+    let equipment_id = request.equipment_id;
+    let not_found = || WorkerError::NotFound(equipment_id.to_string());
+
+    let light_cone = repository::<EquipmentConfig>()
+        .await?
+        .get(equipment_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let promotion = EquipmentPromotionConfig::read()
+        .await?
+        .remove(&equipment_id)
+        .ok_or_else(not_found)?;
+    let exp_type = EquipmentExpType::read()
+        .await?
+        .remove(&light_cone.exp_type)
+        .ok_or_else(|| WorkerError::NotFound(format!("exp type {}", light_cone.exp_type)))?;
+    let exp_items: Vec<ExpMaterial> = EquipmentExpItemConfig::read()
+        .await?
+        .into_values()
+        .map(|item| ExpMaterial {
+            item_id: item.item_id,
+            exp: item.exp_provide,
+            credits: item.coin_cost,
+        })
+        .collect();
+
+    let mut materials = Materials::new();
+    let exp = exp_type.exp_between(request.current.level, request.target.level);
+    add_ascension(
+        (request.current.level, request.current.ascension),
+        (request.target.level, request.target.ascension),
+        &promotion.max_level,
+        &promotion.promotion_cost_list,
+        &mut materials,
+    )?;
+    add_exp(exp, &exp_items, &mut materials);
+    Ok((exp, materials))
+}
+
+pub async fn team_planner(
+    Json(request): Json<TeamPlanRequest>,
+) -> Result<Json<TeamPlan>, WorkerError> {
+    let mut materials = Materials::new();
+    let mut exp = 0;
+    for avatar in request.avatars.iter() {
+        let (avatar_exp, avatar_materials) = avatar_materials(avatar).await?;
+        exp += avatar_exp;
+        merge(&mut materials, avatar_materials);
+    }
+    let mut light_cone_exp = 0;
+    for light_cone in request.light_cones.iter() {
+        let (cone_exp, cone_materials) = light_cone_materials(light_cone).await?;
+        light_cone_exp += cone_exp;
+        merge(&mut materials, cone_materials);
+    }
+
+    let ids: Vec<u32> = materials.keys().copied().collect();
+    let items = repository::<Item>().await?.list(Some(&ids)).await?;
+    Ok(Json(TeamPlan {
+        exp,
+        light_cone_exp,
+        groups: group_materials(&materials, items)?,
+    }))
+}
+
+pub fn merge(materials: &mut Materials, other: Materials) {
+    for (item_id, count) in other {
+        *materials.entry(item_id).or_default() += count;
+    }
+}
+
+pub fn add_items(materials: &mut Materials, items: &[MiniItem]) {
+    for item in items {
+        *materials.entry(item.item_id).or_default() += item.item_num as u64;
+    }
+}
+
+/// checks the `(level, ascension)` pairs against the level caps and adds
+/// the promotion costs between them, `costs[n]` is the cost of going from
+/// ascension `n` to `n + 1`
+fn add_ascension(
+    current: (u32, u32),
+    target: (u32, u32),
+    max_level: &[u32],
+    costs: &[Vec<MiniItem>],
+    materials: &mut Materials,
+) -> Result<(), WorkerError> {
+    for (level, ascension) in [current, target] {
+        let max_level = max_level
+            .get(ascension as usize)
+            .ok_or_else(|| WorkerError::ParseData(format!("no ascension {ascension}")))?;
+        if level == 0 || level > *max_level {
+            return Err(WorkerError::ParseData(format!(
+                "level {level} is outside 1 to {max_level} at ascension {ascension}"
+            )));
+        }
+    }
+    if target.0 < current.0 || target.1 < current.1 {
+        return Err(WorkerError::ParseData(
+            "target level and ascension can't be below the current ones".into(),
+        ));
+    }
+
+    for ascension in current.1..target.1 {
+        if let Some(cost) = costs.get(ascension as usize) {
+            add_items(materials, cost);
+        }
+    }
+    Ok(())
+}
+
+/// the largest EXP materials that don't overshoot, the remainder is rounded
+/// up with the smallest one
+pub fn add_exp(exp: u64, exp_items: &[ExpMaterial], materials: &mut Materials) {
+    let mut exp_items: Vec<&ExpMaterial> = exp_items.iter().filter(|item| item.exp > 0).collect();
+    exp_items.sort_by(|a, b| b.exp.cmp(&a.exp));
+
+    let mut remaining = exp;
+    let mut credits = 0;
+    for (index, item) in exp_items.iter().enumerate() {
+        let item_exp = item.exp as u64;
+        let count = match index == exp_items.len() - 1 {
+            true => (remaining + item_exp - 1) / item_exp,
+            false => remaining / item_exp,
+        };
+        if count > 0 {
+            *materials.entry(item.item_id).or_default() += count;
+            remaining = remaining.saturating_sub(count * item_exp);
+            credits += count * item.credits as u64;
+        }
+    }
+    if credits > 0 {
+        *materials.entry(CREDIT_ID).or_default() += credits;
+    }
+}
+
+/// `material_list[n]` is the cost of going from level `n` to `n + 1`
+fn add_traces(
+    request: &AvatarPlanRequest,
+    traces: &BTreeMap<u32, AvatarSkillTreeConfig>,
+    materials: &mut Materials,
+) -> Result<(), WorkerError> {
+    for (point_id, to) in request.target.traces.iter() {
+        let trace = traces.get(point_id).ok_or_else(|| {
+            WorkerError::NotFound(format!("trace {point_id} of avatar {}", request.avatar_id))
+        })?;
+        let from = request
+            .current
+            .traces
+            .get(point_id)
+            .copied()
+            .unwrap_or_default();
+        if *to > trace.max_level || *to < from {
+            return Err(WorkerError::ParseData(format!(
+                "trace {point_id} can't go from level {from} to {to}, max {}",
+                trace.max_level
+            )));
+        }
+        for level in from..*to {
+            if let Some(cost) = trace.material_list.get(level as usize) {
+                add_items(materials, cost);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// materials grouped by sub type, ordered by item id in and between groups
+pub fn group_materials(
+    materials: &Materials,
+    items: Vec<Item>,
+) -> Result<Vec<MaterialGroup>, WorkerError> {
+    let items: BTreeMap<u32, Item> = items.into_iter().map(|item| (item.id, item)).collect();
+    let mut groups: Vec<MaterialGroup> = Vec::new();
+    for (item_id, count) in materials.iter().filter(|(_, count)| **count > 0) {
+        let item = items
+            .get(item_id)
+            .ok_or_else(|| WorkerError::NotFound(format!("item {item_id}")))?;
+        let material = MaterialCount {
+            item_id: *item_id,
+            name: item.item_name.clone(),
+            rarity: item.rarity,
+            count: *count,
+        };
+        match groups
+            .iter_mut()
+            .find(|group| group.sub_type == item.item_sub_type)
+        {
+            Some(group) => group.items.push(material),
+            None => groups.push(MaterialGroup {
+                sub_type: item.item_sub_type,
+                items: vec![material],
+            }),
+        }
+    }
+    Ok(groups)
+}
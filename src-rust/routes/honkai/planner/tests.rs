@@ -0,0 +1,231 @@
+use super::{
+    add_exp, avatar_planner,
+    inventory::{inventory_planner, resolve, Resolution},
+    team_planner,
+    types::{
+        AvatarPlanRequest, AvatarProgress, ExpMaterial, InventoryRequest, LightConePlanRequest,
+        LightConeProgress, Materials, TeamPlanRequest,
+    },
+    CREDIT_ID,
+};
+use crate::routes::honkai::{
+    dm_api::{character::types::MiniItem, item::compose::ItemComposeConfig},
+    traits::DbData,
+};
+use axum::Json;
+use std::collections::BTreeMap;
+
+fn exp_items() -> Vec<ExpMaterial> {
+    [(211, 1000), (212, 5000), (213, 20000)]
+        .into_iter()
+        .map(|(item_id, exp)| ExpMaterial {
+            item_id,
+            exp,
+            credits: exp / 10,
+        })
+        .collect()
+}
+
+#[test]
+fn exp_materials() {
+    let mut materials = Materials::new();
+    add_exp(46_500, &exp_items(), &mut materials);
+    // 2 * 20000 + 1 * 5000 + 2 * 1000 overshoots by 500
+    assert_eq!(
+        materials,
+        Materials::from([(CREDIT_ID, 4700), (211, 2), (212, 1), (213, 2)])
+    );
+
+    let mut materials = Materials::new();
+    add_exp(0, &exp_items(), &mut materials);
+    assert!(materials.is_empty());
+}
+
+fn seele_to_30_request() -> AvatarPlanRequest {
+    AvatarPlanRequest {
+        avatar_id: 1102,
+        current: AvatarProgress {
+            level: 1,
+            ascension: 0,
+            traces: BTreeMap::from([(1102001, 1)]),
+        },
+        target: AvatarProgress {
+            level: 30,
+            ascension: 1,
+            traces: BTreeMap::from([(1102001, 3), (1102101, 1)]),
+        },
+    }
+}
+
+#[tokio::test]
+async fn seele_to_30() {
+    let Json(plan) = avatar_planner(Json(seele_to_30_request())).await.unwrap();
+    assert_eq!(plan.exp, 150_290);
+
+    let counts: Vec<(u32, u64)> = plan
+        .groups
+        .iter()
+        .flat_map(|group| group.items.iter())
+        .map(|item| (item.item_id, item.count))
+        .collect();
+    assert_eq!(
+        counts,
+        [
+            (CREDIT_ID, 36_600),
+            (211, 1),
+            (212, 2),
+            (213, 7),
+            (110141, 8),
+            (111001, 10)
+        ]
+    );
+    let credits = &plan.groups[0].items[0];
+    assert_eq!(credits.name, "Credit");
+}
+
+#[tokio::test]
+async fn rejects_bad_progress() {
+    let request = AvatarPlanRequest {
+        avatar_id: 1102,
+        current: AvatarProgress {
+            level: 40,
+            ascension: 2,
+            ..Default::default()
+        },
+        // level 30 is past the cap of ascension 0
+        target: AvatarProgress {
+            level: 30,
+            ascension: 0,
+            ..Default::default()
+        },
+    };
+    assert!(avatar_planner(Json(request)).await.is_err());
+}
+
+fn recipe(id: u32, item_id: u32, input: u32, coin_cost: u32) -> ItemComposeConfig {
+    ItemComposeConfig {
+        id,
+        item_id,
+        material_cost: vec![MiniItem {
+            item_id: input,
+            item_num: 3,
+        }],
+        coin_cost,
+    }
+}
+
+#[test]
+fn crafts_down_the_chain() {
+    let recipes = [recipe(1, 111002, 111001, 0), recipe(2, 111003, 111002, 0)];
+    let need = Materials::from([(111003, 2)]);
+    let inventory = Materials::from([(111001, 10), (111002, 1)]);
+    assert_eq!(
+        resolve(&need, &inventory, &recipes),
+        Resolution {
+            craft: vec![(111002, 5, 0), (111003, 2, 0)],
+            farm: Materials::from([(111001, 5)]),
+        }
+    );
+
+    // a cycle must not loop or craft from itself, both of its recipes are
+    // dropped and the item is farmed
+    let recipes = [recipe(1, 1, 2, 0), recipe(2, 2, 1, 0)];
+    assert_eq!(
+        resolve(&Materials::from([(1, 1)]), &Materials::new(), &recipes),
+        Resolution {
+            craft: vec![],
+            farm: Materials::from([(1, 1)]),
+        }
+    );
+}
+
+#[tokio::test]
+async fn fixture_recipes() {
+    let recipes: Vec<ItemComposeConfig> = ItemComposeConfig::read()
+        .await
+        .unwrap()
+        .into_values()
+        .collect();
+    let resolution = resolve(&Materials::from([(110143, 1)]), &Materials::new(), &recipes);
+    assert_eq!(
+        resolution,
+        Resolution {
+            craft: vec![(110142, 3, 300), (110143, 1, 100)],
+            farm: Materials::from([(CREDIT_ID, 400), (110141, 9)]),
+        }
+    );
+}
+
+#[tokio::test]
+async fn seele_shortfall() {
+    let request = InventoryRequest {
+        plans: vec![seele_to_30_request()],
+        light_cones: Vec::new(),
+        inventory: Materials::from([(CREDIT_ID, 40_000), (110141, 3), (111001, 10)]),
+        drops_per_run: BTreeMap::from([(213, 7.0)]),
+    };
+    let Json(shortfall) = inventory_planner(Json(request)).await.unwrap();
+    assert!(shortfall.craft.is_empty());
+
+    let farm: Vec<(u32, u64, Option<u64>)> = shortfall
+        .farm
+        .iter()
+        .map(|step| (step.item_id, step.count, step.runs))
+        .collect();
+    assert_eq!(
+        farm,
+        [
+            (211, 1, Some(1)),
+            (212, 2, Some(1)),
+            (213, 7, Some(1)),
+            (110141, 5, Some(1))
+        ]
+    );
+}
+
+fn day_one_to_30_request() -> LightConePlanRequest {
+    LightConePlanRequest {
+        equipment_id: 21002,
+        current: LightConeProgress {
+            level: 1,
+            ascension: 0,
+        },
+        target: LightConeProgress {
+            level: 30,
+            ascension: 1,
+        },
+    }
+}
+
+#[tokio::test]
+async fn team_shopping_list() {
+    let request = TeamPlanRequest {
+        avatars: vec![seele_to_30_request()],
+        light_cones: vec![day_one_to_30_request()],
+    };
+    let Json(plan) = team_planner(Json(request)).await.unwrap();
+    assert_eq!(plan.exp, 150_290);
+    assert_eq!(plan.light_cone_exp, 23_240);
+
+    let counts: Vec<(u32, u64)> = plan
+        .groups
+        .iter()
+        .flat_map(|group| group.items.iter())
+        .map(|item| (item.item_id, item.count))
+        .collect();
+    // the light cone adds 5350 credits, 3/2/3 aethers and 4 of 111001
+    assert_eq!(
+        counts,
+        [
+            (CREDIT_ID, 41_950),
+            (211, 1),
+            (212, 2),
+            (213, 7),
+            (231, 3),
+            (232, 2),
+            (233, 3),
+            (110141, 8),
+            (111001, 14)
+        ]
+    );
+}
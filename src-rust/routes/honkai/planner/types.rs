@@ -0,0 +1,134 @@
+use crate::routes::honkai::dm_api::item::types::{ItemRarity, ItemSubType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// item id to count
+pub type Materials = BTreeMap<u32, u64>;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AvatarPlanRequest {
+    pub avatar_id: u32,
+    pub current: AvatarProgress,
+    pub target: AvatarProgress,
+}
+
+/// one row of the planner spreadsheet
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AvatarProgress {
+    pub level: u32,
+    /// promotion count, 0 to 6
+    pub ascension: u32,
+    /// skill and trace levels by `point_id`, a missing point is at level 0
+    /// in `current` and unchanged in `target`
+    #[serde(default)]
+    pub traces: BTreeMap<u32, u32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LightConePlanRequest {
+    pub equipment_id: u32,
+    pub current: LightConeProgress,
+    pub target: LightConeProgress,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LightConeProgress {
+    pub level: u32,
+    /// promotion count, 0 to 6
+    pub ascension: u32,
+}
+
+/// a whole team, planned as one shopping list
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TeamPlanRequest {
+    #[serde(default)]
+    pub avatars: Vec<AvatarPlanRequest>,
+    #[serde(default)]
+    pub light_cones: Vec<LightConePlanRequest>,
+}
+
+/// an EXP material with the credits feeding one costs
+#[derive(Debug, Clone)]
+pub struct ExpMaterial {
+    pub item_id: u32,
+    pub exp: u32,
+    pub credits: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AvatarPlan {
+    pub avatar_id: u32,
+    /// character EXP the level ups need
+    pub exp: u64,
+    pub groups: Vec<MaterialGroup>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LightConePlan {
+    pub equipment_id: u32,
+    /// light cone EXP the level ups need
+    pub exp: u64,
+    pub groups: Vec<MaterialGroup>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TeamPlan {
+    /// character EXP of every avatar
+    pub exp: u64,
+    /// light cone EXP of every light cone
+    pub light_cone_exp: u64,
+    pub groups: Vec<MaterialGroup>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MaterialGroup {
+    pub sub_type: ItemSubType,
+    pub items: Vec<MaterialCount>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MaterialCount {
+    pub item_id: u32,
+    pub name: String,
+    pub rarity: ItemRarity,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct InventoryRequest {
+    /// upgrades to plan for, summed together
+    #[serde(default)]
+    pub plans: Vec<AvatarPlanRequest>,
+    #[serde(default)]
+    pub light_cones: Vec<LightConePlanRequest>,
+    /// owned count by item id
+    #[serde(default)]
+    pub inventory: Materials,
+    /// expected drops of one run by item id, overrides the defaults
+    #[serde(default)]
+    pub drops_per_run: BTreeMap<u32, f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Shortfall {
+    /// in crafting order, lower tiers are crafted from farmed materials too
+    pub craft: Vec<CraftStep>,
+    pub farm: Vec<FarmStep>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CraftStep {
+    pub item_id: u32,
+    pub name: String,
+    pub count: u64,
+    pub credits: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FarmStep {
+    pub item_id: u32,
+    pub name: String,
+    pub count: u64,
+    /// `None` when there's no drop rate for the item
+    pub runs: Option<u64>,
+}
@@ -9,18 +9,17 @@ use std::collections::HashMap;
 use tracing::error;
 
 pub mod rpc;
+#[cfg(test)]
+mod tests;
 pub mod types;
 
 pub async fn handle(
     rpayload: Result<Json<ProbabilityRatePayload>, JsonRejection>,
 ) -> Result<Json<ProbabilityRateResponse>, WorkerError> {
-    if rpayload.is_err() {
-        let err = rpayload.unwrap_err();
+    let Json(payload) = rpayload.map_err(|err| {
         error!("{}", err.body_text());
-        return Err(WorkerError::ParseData(err.body_text()));
-    }
-    // safe unwrap
-    let Json(payload) = rpayload.unwrap();
+        WorkerError::ParseData(err.body_text())
+    })?;
     let banner = match payload.banner {
         BannerType::Ssr => Banner::char_ssr().to_internal(pity_rate(0.6, 74)),
         BannerType::Sr => Banner::char_sr().to_internal(pity_rate(5.1, 9)),
@@ -1,6 +1,6 @@
 use self::probabilityrate::probability_rate_service_server::*;
 use super::types::{ProbabilityRatePayload, ProbabilityRateResponse, ReducedSim};
-use crate::routes::honkai::probability_rate::handle;
+use crate::{handler::error::WorkerError, routes::honkai::probability_rate::handle};
 use axum::{
     routing::{any_service, MethodRouter},
     Json,
@@ -18,8 +18,8 @@ impl ProbabilityRateService for ProbabilityRateResponse {
         &self,
         req: Request<probabilityrate::ProbabilityRatePayload>,
     ) -> Result<Response<probabilityrate::ProbabilityRateResponse>, Status> {
-        let req = req.into_inner();
-        let Json(data) = handle(Ok(Json(req.into()))).await?;
+        let payload: ProbabilityRatePayload = req.into_inner().try_into()?;
+        let Json(data) = handle(Ok(Json(payload))).await?;
         Ok(Response::new(data.into()))
     }
 }
@@ -46,16 +46,21 @@ impl From<ReducedSim> for probabilityrate::ReducedSim {
     }
 }
 
-impl From<probabilityrate::ProbabilityRatePayload> for ProbabilityRatePayload {
-    fn from(value: probabilityrate::ProbabilityRatePayload) -> Self {
-        ProbabilityRatePayload {
+impl TryFrom<probabilityrate::ProbabilityRatePayload> for ProbabilityRatePayload {
+    type Error = WorkerError;
+
+    fn try_from(value: probabilityrate::ProbabilityRatePayload) -> Result<Self, WorkerError> {
+        Ok(ProbabilityRatePayload {
             current_eidolon: value.current_eidolon,
             pity: value.pity,
             pulls: value.pulls,
             next_guaranteed: value.next_guaranteed,
             enpitomized_pity: value.enpitomized_pity,
-            banner: value.banner.try_into().unwrap(),
-        }
+            banner: value
+                .banner
+                .try_into()
+                .map_err(|_| WorkerError::ParseData(format!("unknown banner {}", value.banner)))?,
+        })
     }
 }
 
@@ -0,0 +1,31 @@
+use super::{rpc::probabilityrate, types::ProbabilityRatePayload};
+use crate::handler::error::WorkerError;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// feeds random protobuf payloads into the `TryFrom` conversion, unknown
+/// banners should come back as errors instead of panics
+#[test]
+fn rpc_conversion_random_payloads() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    for _ in 0..2000 {
+        let banner = match rng.gen_bool(0.7) {
+            true => rng.gen_range(-2..6),
+            false => rng.gen(),
+        };
+        let payload = probabilityrate::ProbabilityRatePayload {
+            current_eidolon: rng.gen(),
+            pity: rng.gen(),
+            pulls: rng.gen(),
+            next_guaranteed: rng.gen(),
+            enpitomized_pity: rng.gen(),
+            banner,
+        };
+
+        let converted: Result<ProbabilityRatePayload, WorkerError> = payload.try_into();
+        match (0..=2).contains(&banner) {
+            true => assert!(converted.is_ok()),
+            false => assert!(matches!(converted, Err(WorkerError::ParseData(_)))),
+        }
+    }
+}
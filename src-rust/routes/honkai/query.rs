@@ -0,0 +1,291 @@
+use super::dm_api::{
+    character::types::AvatarConfig,
+    equipment::equipment_config::EquipmentConfig,
+    item::types::{Item, ItemType},
+    monster::types::MonsterConfig,
+    relic::{
+        config::{RelicConfig, RelicType},
+        set_config::RelicSetConfig,
+    },
+    types::{Element, Path},
+};
+use crate::{
+    handler::error::WorkerError,
+    routes::endpoint_types::{List, Pagination},
+};
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// filters, sort and page shared by the list endpoints, e.g.
+/// `?rarity=5&path=Destruction&sort=-name&limit=20&cursor=20`
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ListQuery {
+    pub rarity: Option<u8>,
+    pub path: Option<Path>,
+    pub element: Option<Element>,
+    pub release: Option<bool>,
+    pub relic_type: Option<RelicType>,
+    pub item_type: Option<ItemType>,
+    /// `name` or `rarity`, prefixed with `-` for descending order
+    pub sort: Option<String>,
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+/// fields a list endpoint can filter and sort on. A filter on a field the
+/// type doesn't have (`None`) matches nothing
+pub trait Queryable {
+    fn name(&self) -> &str;
+    fn rarity(&self) -> Option<u8> {
+        None
+    }
+    fn path(&self) -> Option<Path> {
+        None
+    }
+    fn element(&self) -> Option<Element> {
+        None
+    }
+    fn release(&self) -> Option<bool> {
+        None
+    }
+    fn relic_type(&self) -> Option<RelicType> {
+        None
+    }
+    fn item_type(&self) -> Option<ItemType> {
+        None
+    }
+}
+
+impl ListQuery {
+    /// filters, sorts and pages `items`. Ties in the sort keep the order of
+    /// `items`, so callers should pass them in a stable order for cursors
+    /// to stay valid between requests
+    pub fn apply<T: Queryable>(&self, items: Vec<T>) -> Result<List<T>, WorkerError> {
+        let mut items: Vec<T> = items
+            .into_iter()
+            .filter(|item| self.matches(item))
+            .collect();
+
+        if let Some(sort) = &self.sort {
+            let (descending, key) = match sort.strip_prefix('-') {
+                Some(key) => (true, key),
+                None => (false, sort.as_str()),
+            };
+            let compare: fn(&T, &T) -> Ordering = match key {
+                "name" => |a, b| a.name().cmp(b.name()),
+                "rarity" => |a, b| a.rarity().cmp(&b.rarity()),
+                _ => {
+                    return Err(WorkerError::ParseData(format!(
+                        "can't sort by {key}, expected name or rarity"
+                    )))
+                }
+            };
+            items.sort_by(|a, b| match descending {
+                true => compare(b, a),
+                false => compare(a, b),
+            });
+        }
+
+        let total = items.len();
+        let offset = match &self.cursor {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| WorkerError::ParseData(format!("bad cursor {cursor}")))?,
+            None => 0,
+        };
+        let end = match self.limit {
+            // an empty page would hand back the same cursor forever
+            Some(0) => return Err(WorkerError::ParseData("limit must be at least 1".into())),
+            Some(limit) => offset.saturating_add(limit).min(total),
+            None => total,
+        };
+        let list: Vec<T> = items
+            .into_iter()
+            .skip(offset)
+            .take(end.saturating_sub(offset))
+            .collect();
+
+        Ok(List {
+            list,
+            pagination: Some(Pagination {
+                total,
+                next_cursor: (end < total).then(|| end.to_string()),
+            }),
+        })
+    }
+
+    fn matches<T: Queryable>(&self, item: &T) -> bool {
+        fn check<V: PartialEq>(filter: Option<V>, value: Option<V>) -> bool {
+            filter.is_none() || filter == value
+        }
+        check(self.rarity, item.rarity())
+            && check(self.path, item.path())
+            && check(self.element, item.element())
+            && check(self.release, item.release())
+            && check(self.relic_type, item.relic_type())
+            && check(self.item_type, item.item_type())
+    }
+}
+
+impl Queryable for AvatarConfig {
+    fn name(&self) -> &str {
+        &self.avatar_name
+    }
+    fn rarity(&self) -> Option<u8> {
+        Some(self.rarity)
+    }
+    fn path(&self) -> Option<Path> {
+        Some(self.avatar_base_type)
+    }
+    fn element(&self) -> Option<Element> {
+        Some(self.damage_type)
+    }
+    fn release(&self) -> Option<bool> {
+        Some(self.release)
+    }
+}
+
+impl Queryable for EquipmentConfig {
+    fn name(&self) -> &str {
+        &self.equipment_name
+    }
+    fn rarity(&self) -> Option<u8> {
+        Some(self.rarity)
+    }
+    fn path(&self) -> Option<Path> {
+        Some(self.avatar_base_type)
+    }
+    fn release(&self) -> Option<bool> {
+        Some(self.release)
+    }
+}
+
+impl Queryable for RelicSetConfig {
+    fn name(&self) -> &str {
+        &self.set_name
+    }
+    fn release(&self) -> Option<bool> {
+        self.release
+    }
+}
+
+impl Queryable for RelicConfig {
+    fn name(&self) -> &str {
+        ""
+    }
+    fn rarity(&self) -> Option<u8> {
+        Some(self.rarity)
+    }
+    fn relic_type(&self) -> Option<RelicType> {
+        Some(self.ttype)
+    }
+}
+
+impl Queryable for Item {
+    fn name(&self) -> &str {
+        &self.item_name
+    }
+    fn rarity(&self) -> Option<u8> {
+        Some(self.rarity.stars())
+    }
+    fn item_type(&self) -> Option<ItemType> {
+        Some(self.item_main_type)
+    }
+}
+
+impl Queryable for MonsterConfig {
+    fn name(&self) -> &str {
+        &self.monster_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ListQuery, Queryable};
+    use crate::routes::honkai::dm_api::types::{Element, Path};
+
+    struct Unit(&'static str, u8, Element);
+
+    impl Queryable for Unit {
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn rarity(&self) -> Option<u8> {
+            Some(self.1)
+        }
+        fn element(&self) -> Option<Element> {
+            Some(self.2)
+        }
+    }
+
+    fn units() -> Vec<Unit> {
+        vec![
+            Unit("March 7th", 4, Element::Ice),
+            Unit("Seele", 5, Element::Quantum),
+            Unit("Himeko", 5, Element::Fire),
+            Unit("Gepard", 5, Element::Ice),
+        ]
+    }
+
+    fn names(list: &[Unit]) -> Vec<&str> {
+        list.iter().map(|unit| unit.0).collect()
+    }
+
+    #[test]
+    fn filter_and_sort() {
+        let query = ListQuery {
+            rarity: Some(5),
+            sort: Some("-name".into()),
+            ..Default::default()
+        };
+        let list = query.apply(units()).unwrap();
+        assert_eq!(names(&list.list), ["Seele", "Himeko", "Gepard"]);
+
+        let query = ListQuery {
+            element: Some(Element::Ice),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&query.apply(units()).unwrap().list),
+            ["March 7th", "Gepard"]
+        );
+
+        // units have no path, so a path filter matches nothing
+        let query = ListQuery {
+            path: Some(Path::Hunt),
+            ..Default::default()
+        };
+        assert!(query.apply(units()).unwrap().list.is_empty());
+    }
+
+    #[test]
+    fn cursor_pages() {
+        let mut query = ListQuery {
+            sort: Some("name".into()),
+            limit: Some(3),
+            ..Default::default()
+        };
+        let first = query.apply(units()).unwrap();
+        assert_eq!(names(&first.list), ["Gepard", "Himeko", "March 7th"]);
+        let pagination = first.pagination.unwrap();
+        assert_eq!(pagination.total, 4);
+
+        query.cursor = pagination.next_cursor;
+        let last = query.apply(units()).unwrap();
+        assert_eq!(names(&last.list), ["Seele"]);
+        assert_eq!(last.pagination.unwrap().next_cursor, None);
+
+        query.limit = Some(0);
+        assert!(query.apply(units()).is_err());
+    }
+
+    #[test]
+    fn bad_sort() {
+        let query = ListQuery {
+            sort: Some("speed".into()),
+            ..Default::default()
+        };
+        assert!(query.apply(units()).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use super::{
+    dm_api::{
+        character::{eidolon::AvatarRankConfig, types::AvatarConfig},
+        character_skill::types::{AvatarSkillConfig, AvatarSkillTreeConfig},
+        equipment::{
+            equipment_config::EquipmentConfig, equipment_skill_config::EquipmentSkillConfig,
+        },
+        item::types::Item,
+    },
+    traits::DbData,
+};
+use crate::{
+    builder::{
+        config::{env_config, DataBackend},
+        get_db_client,
+    },
+    handler::error::WorkerError,
+};
+use async_trait::async_trait;
+use libsql_client::{args, Client, Statement, Value};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{marker::PhantomData, sync::Arc};
+use tokio::sync::OnceCell;
+
+/// shared client of the sql backend, connecting is too slow to do per
+/// request
+static SQL_CLIENT: OnceCell<Arc<Client>> = OnceCell::const_new();
+
+/// a dataset type that is also seeded as a row of `TABLE`, the whole record
+/// is serialized in the `data` column
+pub trait Record: DbData + Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    const TABLE: &'static str;
+}
+
+/// read access to records by id, handlers get one from `repository` so the
+/// backend can be switched in the config
+#[async_trait]
+pub trait Repository<T>: Send + Sync {
+    async fn get(&self, id: u32) -> Result<Option<T>, WorkerError>;
+
+    /// records in id order, every record if `ids` is `None`. Unknown ids are
+    /// skipped
+    async fn list(&self, ids: Option<&[u32]>) -> Result<Vec<T>, WorkerError>;
+}
+
+/// reads the dataset files through `DbData::read`
+pub struct JsonRepository<T>(PhantomData<T>);
+
+/// reads the `data` column of the seeded database
+pub struct SqlRepository<T> {
+    client: Arc<Client>,
+    record: PhantomData<T>,
+}
+
+impl<T> Default for JsonRepository<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> SqlRepository<T> {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            record: PhantomData,
+        }
+    }
+}
+
+/// repository of the configured backend
+pub async fn repository<T: Record>() -> Result<Box<dyn Repository<T>>, WorkerError>
+where
+    T::TLocal: IntoIterator<Item = (u32, T)>,
+{
+    match env_config().data.backend {
+        DataBackend::Json => Ok(Box::<JsonRepository<T>>::default()),
+        DataBackend::Sql => {
+            let client = SQL_CLIENT
+                .get_or_try_init(|| async { get_db_client().await.map(Arc::new) })
+                .await?;
+            Ok(Box::new(SqlRepository::new(client.clone())))
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Record> Repository<T> for JsonRepository<T>
+where
+    T::TLocal: IntoIterator<Item = (u32, T)>,
+{
+    async fn get(&self, id: u32) -> Result<Option<T>, WorkerError> {
+        Ok(T::read()
+            .await?
+            .into_iter()
+            .find_map(|(key, value)| (key == id).then_some(value)))
+    }
+
+    async fn list(&self, ids: Option<&[u32]>) -> Result<Vec<T>, WorkerError> {
+        let mut records: Vec<(u32, T)> = T::read()
+            .await?
+            .into_iter()
+            .filter(|(key, _)| ids.map_or(true, |ids| ids.contains(key)))
+            .collect();
+        records.sort_by_key(|(key, _)| *key);
+        Ok(records.into_iter().map(|(_, value)| value).collect())
+    }
+}
+
+#[async_trait]
+impl<T: Record> Repository<T> for SqlRepository<T> {
+    async fn get(&self, id: u32) -> Result<Option<T>, WorkerError> {
+        let result = self
+            .client
+            .execute(Statement::with_args(
+                format!("SELECT data FROM {} WHERE id = ?", T::TABLE),
+                args!(id),
+            ))
+            .await?;
+        result
+            .rows
+            .first()
+            .map(|row| parse_data(row.values.first()))
+            .transpose()
+    }
+
+    async fn list(&self, ids: Option<&[u32]>) -> Result<Vec<T>, WorkerError> {
+        let statement = match ids {
+            Some(ids) => {
+                let placeholders = vec!["?"; ids.len()].join(", ");
+                Statement::with_args(
+                    format!(
+                        "SELECT data FROM {} WHERE id IN ({placeholders}) ORDER BY id",
+                        T::TABLE
+                    ),
+                    ids,
+                )
+            }
+            None => Statement::new(format!("SELECT data FROM {} ORDER BY id", T::TABLE)),
+        };
+        let result = self.client.execute(statement).await?;
+        result
+            .rows
+            .iter()
+            .map(|row| parse_data(row.values.first()))
+            .collect()
+    }
+}
+
+fn parse_data<T: Record>(value: Option<&Value>) -> Result<T, WorkerError> {
+    match value {
+        Some(Value::Text { value }) => Ok(serde_json::from_str(value)?),
+        _ => Err(WorkerError::ParseData(format!(
+            "{} has rows without data, reseed the database",
+            T::TABLE
+        ))),
+    }
+}
+
+impl Record for AvatarConfig {
+    const TABLE: &'static str = "honkai_avatar";
+}
+
+impl Record for AvatarSkillConfig {
+    const TABLE: &'static str = "honkai_skill";
+}
+
+impl Record for AvatarSkillTreeConfig {
+    const TABLE: &'static str = "honkai_trace";
+}
+
+impl Record for AvatarRankConfig {
+    const TABLE: &'static str = "honkai_eidolon";
+}
+
+impl Record for EquipmentConfig {
+    const TABLE: &'static str = "honkai_lightCone";
+}
+
+impl Record for EquipmentSkillConfig {
+    const TABLE: &'static str = "honkai_lightConeSkill";
+}
+
+impl Record for Item {
+    const TABLE: &'static str = "honkai_item";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonRepository, Repository, SqlRepository};
+    use crate::{
+        builder::{get_db_client_for, migration::migrate_up},
+        routes::honkai::dm_api::{
+            character::types::AvatarConfig, equipment::equipment_config::EquipmentConfig,
+        },
+        runnables::db::DbTable,
+    };
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn sql_matches_json() {
+        let path = std::env::temp_dir().join("nas-ws-repository.sqlite");
+        let _ = std::fs::remove_file(&path);
+        let client = get_db_client_for(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        migrate_up(&client, None).await.unwrap();
+        DbTable::AvatarConfig.seed(&client).await.unwrap();
+        DbTable::EquipmentConfig.seed(&client).await.unwrap();
+        let client = Arc::new(client);
+
+        let json = JsonRepository::<AvatarConfig>::default();
+        let sql = SqlRepository::<AvatarConfig>::new(client.clone());
+        let json_list = serde_json::to_value(json.list(None).await.unwrap()).unwrap();
+        let sql_list = serde_json::to_value(sql.list(None).await.unwrap()).unwrap();
+        assert_eq!(json_list, sql_list);
+
+        let seele = sql.get(1102).await.unwrap().unwrap();
+        assert_eq!(seele.avatar_name, "Seele");
+        assert!(sql.get(9999).await.unwrap().is_none());
+        assert_eq!(sql.list(Some(&[1003, 1001])).await.unwrap().len(), 2);
+
+        let lc_json = JsonRepository::<EquipmentConfig>::default();
+        let lc_sql = SqlRepository::<EquipmentConfig>::new(client);
+        assert_eq!(
+            serde_json::to_value(lc_json.list(Some(&[21002])).await.unwrap()).unwrap(),
+            serde_json::to_value(lc_sql.list(Some(&[21002])).await.unwrap()).unwrap()
+        );
+    }
+}
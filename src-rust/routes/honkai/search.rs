@@ -0,0 +1,239 @@
+use super::{
+    alias::AliasRegistry,
+    dm_api::{
+        character::types::AvatarConfig, equipment::equipment_config::EquipmentConfig,
+        item::types::Item, monster::types::MonsterConfig, relic::set_config::RelicSetConfig,
+    },
+    repository::repository,
+    traits::{DatasetCache, DbData},
+};
+use crate::handler::error::WorkerError;
+use axum::{extract::Query, Json};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// built on the first search of every dataset snapshot, a refresh that
+/// switches snapshots brings new names in
+static SEARCH_INDEX: DatasetCache<SearchIndex> = DatasetCache::new();
+
+/// added to the fuzzy score when the query is exactly one of the terms, so
+/// "jy" ranks Jing Yuan above every name that merely contains a j and a y
+const EXACT_BONUS: i64 = 1000;
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    Character,
+    LightCone,
+    RelicSet,
+    Item,
+    Enemy,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: u32,
+    pub name: String,
+    pub score: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// only search this kind of entity
+    pub kind: Option<SearchKind>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+#[derive(Debug, Clone)]
+struct SearchEntry {
+    kind: SearchKind,
+    id: u32,
+    name: String,
+    /// normalized name and its aliases
+    terms: Vec<String>,
+}
+
+/// in-memory index of every searchable name
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    entries: Vec<SearchEntry>,
+}
+
+/// lowercase alphanumerics only, "Dan Heng • Imbibitor Lunae" becomes
+/// "danhengimbibitorlunae"
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// first letter of every word, "Dan Heng • Imbibitor Lunae" becomes "dhil"
+fn acronym(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl SearchIndex {
+    /// shared index of the current dataset
+    pub async fn get() -> Result<Arc<SearchIndex>, WorkerError> {
+        SEARCH_INDEX.get_or_try_init(SearchIndex::build).await
+    }
+
+    /// the next search builds the index again, for aliases added at runtime
+    pub fn invalidate() {
+        SEARCH_INDEX.clear();
+    }
+
+    pub async fn build() -> Result<SearchIndex, WorkerError> {
+        let mut index = SearchIndex::default();
+        for avatar in repository::<AvatarConfig>().await?.list(None).await? {
+            index.insert(SearchKind::Character, avatar.avatar_id, &avatar.avatar_name);
+        }
+        for lc in repository::<EquipmentConfig>().await?.list(None).await? {
+            index.insert(SearchKind::LightCone, lc.equipment_id, &lc.equipment_name);
+        }
+        let mut relic_sets: Vec<RelicSetConfig> =
+            RelicSetConfig::read().await?.into_values().collect();
+        relic_sets.sort_by_key(|set| set.set_id);
+        for set in relic_sets {
+            index.insert(SearchKind::RelicSet, set.set_id, &set.set_name);
+        }
+        for item in repository::<Item>().await?.list(None).await? {
+            index.insert(SearchKind::Item, item.id, &item.item_name);
+        }
+        let mut monsters: Vec<MonsterConfig> = MonsterConfig::read().await?.into_values().collect();
+        monsters.sort_by_key(|monster| monster.monster_id);
+        for monster in monsters {
+            index.insert(SearchKind::Enemy, monster.monster_id, &monster.monster_name);
+        }
+
+        for entry in AliasRegistry::global().await?.entries() {
+            index.add_aliases(entry.kind, entry.id, entry.aliases);
+        }
+        Ok(index)
+    }
+
+    pub fn insert(&mut self, kind: SearchKind, id: u32, name: &str) {
+        let mut terms = vec![normalize(name), acronym(name)];
+        terms.retain(|term| !term.is_empty());
+        terms.dedup();
+        self.entries.push(SearchEntry {
+            kind,
+            id,
+            name: name.to_owned(),
+            terms,
+        });
+    }
+
+    /// extra terms for an inserted entity, aliases of unknown ids are skipped
+    pub fn add_aliases(&mut self, kind: SearchKind, id: u32, aliases: Vec<String>) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.kind == kind && entry.id == id)
+        {
+            for alias in aliases.iter().map(|alias| normalize(alias)) {
+                if !alias.is_empty() && !entry.terms.contains(&alias) {
+                    entry.terms.push(alias);
+                }
+            }
+        }
+    }
+
+    /// hits sorted by descending score, ties keep the insertion order
+    pub fn search(&self, query: &str, kind: Option<SearchKind>, limit: usize) -> Vec<SearchHit> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let matcher = SkimMatcherV2::default();
+
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .iter()
+            .filter(|entry| kind.map_or(true, |kind| entry.kind == kind))
+            .filter_map(|entry| {
+                let score = entry
+                    .terms
+                    .iter()
+                    .filter_map(|term| {
+                        let bonus = if *term == query { EXACT_BONUS } else { 0 };
+                        matcher.fuzzy_match(term, &query).map(|score| score + bonus)
+                    })
+                    .max()?;
+                Some(SearchHit {
+                    kind: entry.kind,
+                    id: entry.id,
+                    name: entry.name.clone(),
+                    score,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.cmp(&a.score));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// id of the best match of `kind`
+    pub fn best(&self, query: &str, kind: SearchKind) -> Option<u32> {
+        self.search(query, Some(kind), 1).first().map(|hit| hit.id)
+    }
+}
+
+pub async fn search(Query(query): Query<SearchQuery>) -> Result<Json<Vec<SearchHit>>, WorkerError> {
+    let index = SearchIndex::get().await?;
+    Ok(Json(index.search(&query.q, query.kind, query.limit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{acronym, normalize, SearchIndex, SearchKind};
+
+    #[test]
+    fn aliases() {
+        assert_eq!(normalize("Silver Wolf"), "silverwolf");
+        assert_eq!(acronym("Dan Heng • Imbibitor Lunae"), "dhil");
+        assert_eq!(acronym("Jing Yuan"), "jy");
+        assert_eq!(acronym("March 7th's Eidolon"), "m7e");
+
+        let mut index = SearchIndex::default();
+        index.insert(SearchKind::Character, 1213, "Dan Heng • Imbibitor Lunae");
+        index.insert(SearchKind::Character, 1204, "Jing Yuan");
+        index.insert(SearchKind::Character, 1006, "Silver Wolf");
+        index.insert(SearchKind::Item, 1, "Jade Yolk");
+
+        assert_eq!(index.search("DHIL", None, 1)[0].id, 1213);
+        assert_eq!(index.search("JY", None, 1)[0].id, 1204);
+        assert_eq!(index.search("silverwolf", None, 1)[0].id, 1006);
+        assert_eq!(index.best("jy", SearchKind::Item), Some(1));
+        assert!(index.search("", None, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn fixture_index() {
+        let index = SearchIndex::get().await.unwrap();
+
+        let hits = index.search("himeko", None, 10);
+        assert_eq!(hits[0].kind, SearchKind::Character);
+        assert_eq!(hits[0].id, 1003);
+        assert!(hits.iter().any(|hit| hit.name == "Himeko's Eidolon"));
+        assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        assert_eq!(index.best("potwc", SearchKind::RelicSet), Some(101));
+        assert_eq!(index.best("day one", SearchKind::LightCone), Some(21002));
+    }
+}
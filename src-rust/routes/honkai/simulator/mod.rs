@@ -0,0 +1,305 @@
+use self::types::{AvEffect, ChildLog, Event, Log, SimulatorRequest, SimulatorUnit, SkillCost};
+use super::{
+    dm_api::{
+        character::{promotion_config::AvatarPromotionConfig, types::AvatarConfig},
+        character_skill::types::AvatarSkillConfig,
+        types::SkillType,
+    },
+    repository::repository,
+    traits::DbData,
+};
+use crate::{handler::error::WorkerError, routes::endpoint_types::List};
+use axum::Json;
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// action value of the first cycle
+pub const FIRST_CYCLE_AV: f64 = 150.0;
+/// action value of every later cycle
+pub const CYCLE_AV: f64 = 100.0;
+/// a full turn takes this over SPD in action value
+pub const TURN_DISTANCE: f64 = 10000.0;
+pub const MAX_SKILL_POINTS: u32 = 5;
+/// stops teams advancing each other forever without the clock moving
+const MAX_TURNS: usize = 10_000;
+const MAX_CYCLES: u32 = 100;
+
+pub async fn av_simulator(
+    Json(request): Json<SimulatorRequest>,
+) -> Result<Json<List<Log>>, WorkerError> {
+    if request.cycles > MAX_CYCLES {
+        return Err(WorkerError::ParseData(format!(
+            "at most {MAX_CYCLES} cycles can be simulated"
+        )));
+    }
+    let ids: Vec<u32> = request.team.iter().map(|avatar| avatar.avatar_id).collect();
+    let avatars = repository::<AvatarConfig>().await?.list(Some(&ids)).await?;
+    let promotions = AvatarPromotionConfig::read().await?;
+    let skill_ids: Vec<u32> = avatars
+        .iter()
+        .flat_map(|avatar| avatar.skill_list.iter().copied())
+        .collect();
+    let skills = repository::<AvatarSkillConfig>()
+        .await?
+        .list(Some(&skill_ids))
+        .await?;
+
+    let mut units = Vec::new();
+    for member in request.team.iter() {
+        let avatar = avatars
+            .iter()
+            .find(|avatar| avatar.avatar_id == member.avatar_id)
+            .ok_or(WorkerError::NotFound(member.avatar_id.to_string()))?;
+        let speed_base = promotions
+            .get(&avatar.avatar_id)
+            .ok_or(WorkerError::NotFound(format!(
+                "promotion of {}",
+                avatar.avatar_id
+            )))?
+            .speed_base;
+        let speed = speed_base * (1.0 + member.speed_percent) + member.speed_flat;
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(WorkerError::ParseData(format!(
+                "{} would have {speed} SPD",
+                avatar.avatar_name
+            )));
+        }
+
+        let skill_of = |ttype: SkillType| {
+            skills.iter().find(|skill| {
+                avatar.skill_list.contains(&skill.skill_id) && skill.attack_type == Some(ttype)
+            })
+        };
+        let cost = |ttype: SkillType| {
+            skill_of(ttype).map_or(SkillCost::default(), |skill| SkillCost {
+                bp_need: skill.bpneed.map_or(0, |param| param.value as u32),
+                bp_add: skill.bpadd.map_or(0, |param| param.value as u32),
+                energy: skill.spbase.map_or(0.0, |param| param.value),
+            })
+        };
+        let max_energy = skill_of(SkillType::Ultra)
+            .and_then(|skill| skill.spneed)
+            .map_or(avatar.spneed as f64, |param| param.value);
+
+        units.push(SimulatorUnit {
+            avatar_id: avatar.avatar_id,
+            name: avatar.avatar_name.clone(),
+            speed,
+            rotation: member.rotation.clone(),
+            normal: cost(SkillType::Normal),
+            skill: cost(SkillType::BPSkill),
+            ultimate: cost(SkillType::Ultra),
+            max_energy,
+            energy: max_energy * member.energy.clamp(0.0, 1.0),
+        });
+    }
+
+    Ok(Json(List::new(simulate(
+        units,
+        &request.effects,
+        request.cycles,
+        request.skill_points,
+    ))))
+}
+
+/// cycle an action at `elapsed` AV falls in, from 0
+pub fn cycle_of(elapsed: f64) -> u32 {
+    if elapsed <= FIRST_CYCLE_AV {
+        0
+    } else {
+        ((elapsed - FIRST_CYCLE_AV) / CYCLE_AV).ceil() as u32
+    }
+}
+
+/// turns of `units` over the first `cycles` cycles. Ties in action value go
+/// to the earlier team slot, ultimates are used as soon as they are charged
+/// and logged as an extra turn
+pub fn simulate(
+    mut units: Vec<SimulatorUnit>,
+    effects: &[AvEffect],
+    cycles: u32,
+    skill_points: u32,
+) -> Vec<Log> {
+    let mut logs = Vec::new();
+    if units.is_empty() || cycles == 0 {
+        return logs;
+    }
+    let limit = FIRST_CYCLE_AV + CYCLE_AV * (cycles - 1) as f64;
+    let mut av: Vec<f64> = units
+        .iter()
+        .map(|unit| TURN_DISTANCE / unit.speed)
+        .collect();
+    let mut turns = vec![0usize; units.len()];
+    let mut sp = skill_points.min(MAX_SKILL_POINTS);
+    let mut elapsed = 0.0;
+
+    while logs.len() < MAX_TURNS {
+        let (actor, wait) = av
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        if elapsed + wait > limit {
+            break;
+        }
+        elapsed += wait;
+        av.iter_mut().for_each(|left| *left -= wait);
+
+        let unit = &units[actor];
+        let planned = if unit.rotation.is_empty() {
+            SkillType::Normal
+        } else {
+            unit.rotation[turns[actor] % unit.rotation.len()]
+        };
+        let action = match planned {
+            SkillType::BPSkill if sp >= unit.skill.bp_need => SkillType::BPSkill,
+            _ => SkillType::Normal,
+        };
+        let cost = match action {
+            SkillType::BPSkill => unit.skill,
+            _ => unit.normal,
+        };
+        turns[actor] += 1;
+
+        let mut children = Vec::new();
+        let before = sp;
+        sp = (sp.saturating_sub(cost.bp_need) + cost.bp_add).min(MAX_SKILL_POINTS);
+        if sp != before {
+            children.push(child(
+                Event::SPChange,
+                unit,
+                unit,
+                sp,
+                sp as f32 - before as f32,
+                "",
+            ));
+        }
+
+        av[actor] = TURN_DISTANCE / unit.speed;
+        children.push(child(
+            Event::TurnReset,
+            unit,
+            unit,
+            sp,
+            av[actor] as f32,
+            "reset",
+        ));
+        apply_effects(&units, actor, action, effects, &mut av, sp, &mut children);
+
+        let unit = &mut units[actor];
+        unit.energy = (unit.energy + cost.energy).min(unit.max_energy);
+        let ultimate = unit.max_energy > 0.0 && unit.energy >= unit.max_energy;
+        if ultimate {
+            unit.energy = unit.ultimate.energy;
+        }
+        push_turn(&mut logs, &units[actor], sp, elapsed, action, children);
+
+        // used right after the turn, without taking one of its own
+        if ultimate {
+            let mut children = Vec::new();
+            apply_effects(
+                &units,
+                actor,
+                SkillType::Ultra,
+                effects,
+                &mut av,
+                sp,
+                &mut children,
+            );
+            push_turn(
+                &mut logs,
+                &units[actor],
+                sp,
+                elapsed,
+                SkillType::Ultra,
+                children,
+            );
+        }
+    }
+    logs
+}
+
+fn push_turn(
+    logs: &mut Vec<Log>,
+    unit: &SimulatorUnit,
+    sp: u32,
+    elapsed: f64,
+    action: SkillType,
+    mut children: Vec<ChildLog>,
+) {
+    for (index, child) in children.iter_mut().enumerate() {
+        child.event_index = index as u32;
+    }
+    logs.push(Log {
+        event_name: Event::TurnEnd,
+        event_index: logs.len() as u32,
+        fooo: unit.name.clone(),
+        bar: sp,
+        bazz: elapsed as f32,
+        abc: format!("{action:?}"),
+        sss: format!("cycle {}", cycle_of(elapsed)),
+        children,
+    });
+}
+
+fn apply_effects(
+    units: &[SimulatorUnit],
+    actor: usize,
+    trigger: SkillType,
+    effects: &[AvEffect],
+    av: &mut [f64],
+    sp: u32,
+    children: &mut Vec<ChildLog>,
+) {
+    let source = &units[actor];
+    for effect in effects
+        .iter()
+        .filter(|effect| effect.source == source.avatar_id && effect.trigger == trigger)
+    {
+        for (index, target) in units.iter().enumerate() {
+            let hit = match effect.target {
+                Some(id) => target.avatar_id == id,
+                None => index != actor,
+            };
+            if !hit {
+                continue;
+            }
+            av[index] = (av[index] - effect.value * TURN_DISTANCE / target.speed).max(0.0);
+            let kind = if effect.value >= 0.0 {
+                "advance"
+            } else {
+                "delay"
+            };
+            children.push(child(
+                Event::TurnReset,
+                target,
+                source,
+                sp,
+                av[index] as f32,
+                kind,
+            ));
+        }
+    }
+}
+
+fn child(
+    event_name: Event,
+    target: &SimulatorUnit,
+    source: &SimulatorUnit,
+    sp: u32,
+    value: f32,
+    kind: &str,
+) -> ChildLog {
+    ChildLog {
+        event_name,
+        event_index: 0,
+        fooo: target.name.clone(),
+        bar: sp,
+        bazz: value,
+        abc: kind.to_owned(),
+        sss: source.name.clone(),
+    }
+}
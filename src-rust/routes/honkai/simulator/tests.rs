@@ -0,0 +1,152 @@
+use super::{
+    av_simulator, cycle_of, simulate,
+    types::{AvEffect, Event, SimulatorAvatar, SimulatorRequest, SimulatorUnit, SkillCost},
+};
+use crate::routes::honkai::dm_api::types::SkillType;
+use axum::Json;
+
+fn unit(avatar_id: u32, name: &str, speed: f64) -> SimulatorUnit {
+    SimulatorUnit {
+        avatar_id,
+        name: name.into(),
+        speed,
+        rotation: vec![SkillType::BPSkill],
+        normal: SkillCost {
+            bp_need: 0,
+            bp_add: 1,
+            energy: 20.0,
+        },
+        skill: SkillCost {
+            bp_need: 1,
+            bp_add: 0,
+            energy: 30.0,
+        },
+        ultimate: SkillCost {
+            bp_need: 0,
+            bp_add: 0,
+            energy: 5.0,
+        },
+        max_energy: 120.0,
+        energy: 0.0,
+    }
+}
+
+#[test]
+fn cycles() {
+    assert_eq!(cycle_of(80.0), 0);
+    assert_eq!(cycle_of(150.0), 0);
+    assert_eq!(cycle_of(150.1), 1);
+    assert_eq!(cycle_of(250.0), 1);
+    assert_eq!(cycle_of(250.1), 2);
+}
+
+#[test]
+fn turn_order_and_skill_points() {
+    let team = vec![unit(1, "A", 100.0), unit(2, "B", 125.0)];
+    let logs = simulate(team, &[], 2, 3);
+
+    let turns: Vec<(&str, f32, u32, &str)> = logs
+        .iter()
+        .map(|log| (log.fooo.as_str(), log.bazz, log.bar, log.abc.as_str()))
+        .collect();
+    // 3 skill points cover three skills, the fourth turn falls back to a
+    // basic attack
+    assert_eq!(
+        turns,
+        [
+            ("B", 80.0, 2, "BPSkill"),
+            ("A", 100.0, 1, "BPSkill"),
+            ("B", 160.0, 0, "BPSkill"),
+            ("A", 200.0, 1, "Normal"),
+            ("B", 240.0, 0, "BPSkill"),
+        ]
+    );
+    assert_eq!(logs[2].sss, "cycle 1");
+    assert_eq!(logs[0].children[0].event_name, Event::SPChange);
+    assert_eq!(logs[0].children[1].event_name, Event::TurnReset);
+
+    assert!(simulate(vec![unit(1, "A", 100.0)], &[], 0, 3).is_empty());
+}
+
+#[test]
+fn action_advance() {
+    let team = vec![unit(1, "A", 100.0), unit(2, "B", 125.0)];
+    let effects = [AvEffect {
+        source: 2,
+        trigger: SkillType::BPSkill,
+        target: Some(1),
+        value: 1.0,
+    }];
+    let logs = simulate(team, &effects, 1, 3);
+
+    let turns: Vec<(&str, f32)> = logs
+        .iter()
+        .map(|log| (log.fooo.as_str(), log.bazz))
+        .collect();
+    assert_eq!(turns, [("B", 80.0), ("A", 80.0)]);
+    let advance = &logs[0].children[2];
+    assert_eq!(
+        (advance.fooo.as_str(), advance.abc.as_str()),
+        ("A", "advance")
+    );
+    assert_eq!(advance.bazz, 0.0);
+}
+
+#[test]
+fn ultimate() {
+    let mut seele = unit(1, "Seele", 100.0);
+    seele.energy = 60.0;
+    let effects = [AvEffect {
+        source: 1,
+        trigger: SkillType::Ultra,
+        target: Some(1),
+        value: 0.5,
+    }];
+    let logs = simulate(vec![seele], &effects, 3, 3);
+
+    // 60 + 30 + 30 charges the ultimate on the second turn, it advances the
+    // next turn by half
+    let turns: Vec<(f32, &str)> = logs
+        .iter()
+        .map(|log| (log.bazz, log.abc.as_str()))
+        .collect();
+    assert_eq!(
+        turns,
+        [
+            (100.0, "BPSkill"),
+            (200.0, "BPSkill"),
+            (200.0, "Ultra"),
+            (250.0, "BPSkill"),
+            (350.0, "Normal"),
+        ]
+    );
+    assert_eq!(logs[2].event_name, Event::TurnEnd);
+    assert_eq!(logs[2].event_index, 2);
+    let advance = &logs[2].children[0];
+    assert_eq!(
+        (advance.event_name, advance.abc.as_str(), advance.bazz),
+        (Event::TurnReset, "advance", 50.0)
+    );
+}
+
+#[tokio::test]
+async fn fixture_team() {
+    let member = |avatar_id| SimulatorAvatar {
+        avatar_id,
+        speed_flat: 0.0,
+        speed_percent: 0.0,
+        rotation: vec![SkillType::BPSkill],
+        energy: 0.5,
+    };
+    let request = SimulatorRequest {
+        team: vec![member(1102), member(1003)],
+        effects: Vec::new(),
+        cycles: 1,
+        skill_points: 3,
+    };
+    // Seele at 115 SPD acts at 86.96 AV, Himeko at 96 SPD at 104.17
+    let Json(logs) = av_simulator(Json(request)).await.unwrap();
+    let names: Vec<&str> = logs.list.iter().map(|log| log.fooo.as_str()).collect();
+    assert_eq!(names, ["Seele", "Himeko"]);
+    assert_eq!(logs.list[1].bar, 1);
+}
@@ -0,0 +1,127 @@
+use crate::routes::honkai::dm_api::types::SkillType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// a turn of the timeline. The field names are the ones the log viewer of
+/// the front end reads
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub event_name: Event,
+    /// turn number, from 0
+    pub event_index: u32,
+    /// name of the character taking the turn
+    pub fooo: String,
+    /// skill points after the turn
+    pub bar: u32,
+    /// action value elapsed since the start of the battle
+    pub bazz: f32,
+    /// action taken, `Normal` or `BPSkill`. An ultimate is logged as a turn
+    /// of its own at the same action value, with `Ultra`
+    pub abc: String,
+    /// cycle of the turn, e.g. `cycle 0`
+    pub sss: String,
+    pub children: Vec<ChildLog>,
+}
+
+/// something that happened during a turn
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildLog {
+    pub event_name: Event,
+    /// order inside the turn
+    pub event_index: u32,
+    /// name of the character affected
+    pub fooo: String,
+    /// skill points after the event
+    pub bar: u32,
+    /// skill point change of `SPChange` and action value left until the next
+    /// turn of `TurnReset`
+    pub bazz: f32,
+    /// `reset`, `advance` or `delay` for `TurnReset`
+    pub abc: String,
+    /// name of the character causing the event
+    pub sss: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum Event {
+    TurnReset,
+    TurnEnd,
+    SPChange,
+}
+
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct SimulatorRequest {
+    pub team: Vec<SimulatorAvatar>,
+    #[serde(default)]
+    pub effects: Vec<AvEffect>,
+    pub cycles: u32,
+    /// skill points at the start of the battle
+    #[serde(default = "default_skill_points")]
+    pub skill_points: u32,
+}
+
+fn default_skill_points() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct SimulatorAvatar {
+    pub avatar_id: u32,
+    /// SPD from relics and buffs, added after `speed_percent`
+    #[serde(default)]
+    pub speed_flat: f64,
+    /// 0.1 for +10% of the base SPD
+    #[serde(default)]
+    pub speed_percent: f64,
+    /// actions taken in order and repeated, a skill without skill points
+    /// left becomes a basic attack
+    #[serde(default = "default_rotation")]
+    pub rotation: Vec<SkillType>,
+    /// energy at the start of the battle, as a ratio of the ultimate cost
+    #[serde(default = "default_energy")]
+    pub energy: f64,
+}
+
+fn default_rotation() -> Vec<SkillType> {
+    vec![SkillType::BPSkill]
+}
+
+fn default_energy() -> f64 {
+    0.5
+}
+
+/// an action advance (positive `value`) or delay (negative `value`) applied
+/// when `source` uses `trigger`, 0.24 advances by 24% of a full turn
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct AvEffect {
+    pub source: u32,
+    pub trigger: SkillType,
+    /// every other team member when `None`
+    pub target: Option<u32>,
+    pub value: f64,
+}
+
+/// skill point and energy change of an action
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SkillCost {
+    pub bp_need: u32,
+    pub bp_add: u32,
+    pub energy: f64,
+}
+
+/// a team member with everything the timeline needs
+#[derive(Debug, Clone)]
+pub struct SimulatorUnit {
+    pub avatar_id: u32,
+    pub name: String,
+    pub speed: f64,
+    pub rotation: Vec<SkillType>,
+    pub normal: SkillCost,
+    pub skill: SkillCost,
+    pub ultimate: SkillCost,
+    /// energy cost of the ultimate
+    pub max_energy: f64,
+    pub energy: f64,
+}
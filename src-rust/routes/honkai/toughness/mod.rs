@@ -0,0 +1,284 @@
+use self::types::{
+    ActionReduction, BreakAttacker, BreakEffect, ElementBreak, MemberBreak, TeamBreak,
+    TeamBreakRequest,
+};
+use super::{
+    dm_api::{
+        character::types::AvatarConfig,
+        character_skill::types::AvatarSkillConfig,
+        monster::{enemy_at_level, types::Enemy},
+        types::Element,
+    },
+    repository::repository,
+};
+use crate::handler::error::WorkerError;
+use axum::Json;
+
+#[cfg(test)]
+mod tests;
+pub mod types;
+
+/// break base damage of an attacker, by level from 1
+#[rustfmt::skip]
+const LEVEL_MULTIPLIERS: [f64; 80] = [
+    // 1-10
+    54.0000, 58.0000, 62.0000, 67.5264, 70.5094,
+    73.5228, 76.5660, 79.6385, 82.7395, 85.8684,
+    // 11-20
+    91.4944, 97.0680, 102.5892, 108.0579, 113.4743,
+    118.8383, 124.1499, 129.4091, 134.6159, 139.7703,
+    // 21-30
+    149.3323, 158.8011, 168.1768, 177.4594, 186.6489,
+    195.7452, 204.7484, 213.6585, 222.4754, 231.1992,
+    // 31-40
+    246.4276, 261.1810, 275.4733, 289.3179, 302.7275,
+    315.7144, 328.2905, 340.4671, 352.2554, 363.6658,
+    // 41-50
+    408.1240, 451.7883, 494.6798, 536.8188, 578.2249,
+    618.9172, 658.9138, 698.2325, 736.8905, 774.9041,
+    // 51-60
+    871.0599, 964.8705, 1056.4206, 1145.7910, 1233.0585,
+    1318.2965, 1401.5750, 1482.9608, 1562.5178, 1640.3068,
+    // 61-70
+    1752.3215, 1861.9011, 1969.1242, 2074.0659, 2176.7983,
+    2277.3904, 2375.9085, 2472.4160, 2566.9739, 2659.6406,
+    // 71-80
+    2780.3044, 2898.6022, 3014.6029, 3128.3729, 3239.9758,
+    3349.4730, 3456.9236, 3562.3845, 3665.9107, 3767.5533,
+];
+/// break base damage of a level 80 attacker
+pub const LEVEL_80_MULTIPLIER: f64 = LEVEL_MULTIPLIERS[79];
+/// upstream toughness per point of the in-game bar
+pub const STANCE_SCALE: f64 = 3.0;
+/// the enemy isn't broken yet when the break damage lands
+const UNBROKEN_MULTIPLIER: f64 = 0.9;
+/// past this many actions the enemy counts as unbreakable
+pub const MAX_ACTIONS: u32 = 10_000;
+
+/// base damage of an attacker at `level`, `custom` wins when set
+pub fn level_multiplier(level: u32, custom: Option<f64>) -> Result<f64, WorkerError> {
+    match (custom, level) {
+        (Some(value), _) if value.is_finite() && value > 0.0 => Ok(value),
+        (Some(value), _) => Err(WorkerError::ParseData(format!(
+            "bad level multiplier {value}"
+        ))),
+        (None, _) => level
+            .checked_sub(1)
+            .and_then(|index| LEVEL_MULTIPLIERS.get(index as usize))
+            .copied()
+            .ok_or(WorkerError::ParseData(format!(
+                "no known break multiplier for level {level}, send a level_multiplier"
+            ))),
+    }
+}
+
+/// `break_effect` or `weakness_break_efficiency` of a member
+fn bonus(name: &str, value: f64) -> Result<f64, WorkerError> {
+    match value.is_finite() && value >= 0.0 {
+        true => Ok(value),
+        false => Err(WorkerError::ParseData(format!("bad {name} {value}"))),
+    }
+}
+
+/// scales break damage with the max toughness of the enemy, `toughness` is in
+/// the upstream unit
+pub fn toughness_multiplier(toughness: f64) -> f64 {
+    0.5 + toughness / STANCE_SCALE / 120.0
+}
+
+fn element_multiplier(element: Element) -> f64 {
+    match element {
+        Element::Physical | Element::Fire => 2.0,
+        Element::Wind => 1.5,
+        Element::Ice | Element::Lightning => 1.0,
+        Element::Quantum | Element::Imaginary => 0.5,
+    }
+}
+
+fn def_multiplier(attacker_level: u32, enemy_level: u32) -> f64 {
+    let attacker = attacker_level as f64 + 20.0;
+    attacker / (enemy_level as f64 + 20.0 + attacker)
+}
+
+/// break damage and effect of `element` against `enemy`
+pub fn element_break(element: Element, attacker: &BreakAttacker, enemy: &Enemy) -> ElementBreak {
+    let base = attacker.level_multiplier;
+    let break_effect = 1.0 + attacker.break_effect;
+    let toughness = toughness_multiplier(enemy.toughness);
+    let mitigation =
+        def_multiplier(attacker.level, enemy.level) * (1.0 - enemy.resistance(element));
+    let elite = enemy.elite_group > 1;
+
+    let effect = |name: &str, turns: u32, damage: f64, action_delay: f64| BreakEffect {
+        name: name.into(),
+        turns,
+        damage_per_turn: damage * break_effect * mitigation,
+        action_delay,
+        speed_reduction: 0.0,
+    };
+    let effect = match element {
+        Element::Physical => {
+            let cap = enemy.hp * if elite { 0.07 } else { 0.16 };
+            effect("Bleed", 2, cap.min(2.0 * base * toughness), 0.0)
+        }
+        Element::Fire => effect("Burn", 2, base, 0.0),
+        Element::Ice => effect("Freeze", 1, base, 0.5),
+        Element::Lightning => effect("Shock", 2, 2.0 * base, 0.0),
+        Element::Wind => {
+            let stacks = if elite { 3.0 } else { 1.0 };
+            effect("Wind Shear", 2, stacks * base, 0.0)
+        }
+        Element::Quantum => effect(
+            "Entanglement",
+            1,
+            0.6 * base * toughness,
+            0.2 * break_effect,
+        ),
+        Element::Imaginary => BreakEffect {
+            speed_reduction: 0.1,
+            ..effect("Imprisonment", 1, 0.0, 0.3 * break_effect)
+        },
+    };
+
+    ElementBreak {
+        element,
+        weak: enemy.weaknesses.contains(&element),
+        break_damage: base
+            * element_multiplier(element)
+            * toughness
+            * break_effect
+            * mitigation
+            * UNBROKEN_MULTIPLIER,
+        effect,
+    }
+}
+
+/// toughness an action takes off when it hits a weakness
+pub fn reduction(stance: f64, weakness_break_efficiency: f64) -> f64 {
+    stance * (1.0 + weakness_break_efficiency)
+}
+
+/// actions needed to bring `toughness` to 0, every actor takes turns in
+/// order and repeats its own list of reductions. `None` when the actors
+/// can't get there in `MAX_ACTIONS`
+pub fn actions_to_break(toughness: f64, actors: &[Vec<f64>]) -> Option<u32> {
+    let per_round: f64 = actors
+        .iter()
+        .filter(|reductions| !reductions.is_empty())
+        .map(|reductions| reductions.iter().sum::<f64>() / reductions.len() as f64)
+        .sum();
+    if toughness <= 0.0 {
+        return Some(0);
+    }
+    if per_round.is_nan() || per_round <= 0.0 {
+        return None;
+    }
+
+    let mut left = toughness;
+    (0..)
+        .flat_map(|round| {
+            actors
+                .iter()
+                .filter(|reductions| !reductions.is_empty())
+                .map(move |reductions| reductions[round % reductions.len()])
+        })
+        .take(MAX_ACTIONS as usize)
+        .position(|reduction| {
+            left -= reduction;
+            left <= 0.0
+        })
+        .map(|index| index as u32 + 1)
+}
+
+/// index into `actors` of whoever lands the break after `actions` actions
+fn breaker_index(actors: &[Vec<f64>], actions: u32) -> Option<usize> {
+    let acting: Vec<usize> = (0..actors.len())
+        .filter(|index| !actors[*index].is_empty())
+        .collect();
+    if actions == 0 || acting.is_empty() {
+        return None;
+    }
+    Some(acting[(actions as usize - 1) % acting.len()])
+}
+
+/// how fast a team breaks an enemy
+pub async fn team_break(
+    Json(request): Json<TeamBreakRequest>,
+) -> Result<Json<TeamBreak>, WorkerError> {
+    let enemy = enemy_at_level(request.monster_id, request.enemy_level).await?;
+    let ids: Vec<u32> = request.team.iter().map(|member| member.avatar_id).collect();
+    let avatars = repository::<AvatarConfig>().await?.list(Some(&ids)).await?;
+    let skill_ids: Vec<u32> = avatars
+        .iter()
+        .flat_map(|avatar| avatar.skill_list.iter().copied())
+        .collect();
+    let skills = repository::<AvatarSkillConfig>()
+        .await?
+        .list(Some(&skill_ids))
+        .await?;
+
+    let mut members = Vec::new();
+    let mut actors = Vec::new();
+    for member in request.team.iter() {
+        let avatar = avatars
+            .iter()
+            .find(|avatar| avatar.avatar_id == member.avatar_id)
+            .ok_or(WorkerError::NotFound(member.avatar_id.to_string()))?;
+        let attacker = BreakAttacker {
+            level: member.level,
+            level_multiplier: level_multiplier(member.level, member.level_multiplier)?,
+            break_effect: bonus("break effect", member.break_effect)?,
+        };
+        let efficiency = bonus(
+            "weakness break efficiency",
+            member.weakness_break_efficiency,
+        )?;
+
+        let reductions: Vec<ActionReduction> = member
+            .rotation
+            .iter()
+            .map(|action| {
+                let skill = skills.iter().find(|skill| {
+                    avatar.skill_list.contains(&skill.skill_id)
+                        && skill.attack_type == Some(*action)
+                });
+                let element = skill
+                    .and_then(|skill| skill.stance_damage_type)
+                    .unwrap_or(avatar.damage_type);
+                let stance = skill
+                    .and_then(|skill| skill.show_stance_list.first())
+                    .map_or(0.0, |param| param.value);
+                ActionReduction {
+                    action: *action,
+                    reduction: if enemy.weaknesses.contains(&element) {
+                        reduction(stance, efficiency)
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        let own: Vec<f64> = reductions.iter().map(|action| action.reduction).collect();
+
+        members.push(MemberBreak {
+            avatar_id: avatar.avatar_id,
+            name: avatar.avatar_name.clone(),
+            element: avatar.damage_type,
+            actions: actions_to_break(enemy.toughness, &[own.clone()]),
+            damage: element_break(avatar.damage_type, &attacker, &enemy),
+            reductions,
+        });
+        actors.push(own);
+    }
+
+    let team_actions = actions_to_break(enemy.toughness, &actors);
+    let breaker = team_actions
+        .and_then(|actions| breaker_index(&actors, actions))
+        .map(|index| members[index].avatar_id);
+    Ok(Json(TeamBreak {
+        enemy,
+        members,
+        team_actions,
+        breaker,
+    }))
+}
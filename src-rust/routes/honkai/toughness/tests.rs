@@ -0,0 +1,124 @@
+use super::{
+    actions_to_break, element_break, level_multiplier, team_break, toughness_multiplier,
+    types::{BreakAttacker, TeamBreakMember, TeamBreakRequest},
+    LEVEL_80_MULTIPLIER, MAX_ACTIONS,
+};
+use crate::routes::honkai::dm_api::{
+    monster::enemy_at_level,
+    types::{Element, SkillType},
+};
+use axum::Json;
+
+const LEVEL_80: BreakAttacker = BreakAttacker {
+    level: 80,
+    level_multiplier: LEVEL_80_MULTIPLIER,
+    break_effect: 0.0,
+};
+
+#[test]
+fn multipliers() {
+    assert_eq!(level_multiplier(80, None).unwrap(), LEVEL_80_MULTIPLIER);
+    assert_eq!(level_multiplier(70, None).unwrap(), 2659.6406);
+    assert_eq!(level_multiplier(1, None).unwrap(), 54.0);
+    assert_eq!(level_multiplier(90, Some(3000.0)).unwrap(), 3000.0);
+    assert!(level_multiplier(0, None).is_err());
+    assert!(level_multiplier(90, None).is_err());
+    assert!(level_multiplier(80, Some(-1.0)).is_err());
+
+    // 30 upstream is a bar of 10
+    assert!((toughness_multiplier(30.0) - (0.5 + 10.0 / 120.0)).abs() < 1e-9);
+}
+
+#[test]
+fn break_counts() {
+    assert_eq!(actions_to_break(240.0, &[vec![60.0]]), Some(4));
+    assert_eq!(actions_to_break(240.0, &[vec![50.0]]), Some(5));
+    assert_eq!(actions_to_break(240.0, &[vec![0.0]]), None);
+    assert_eq!(actions_to_break(240.0, &[]), None);
+    // 30 + 0 + 90 + 0 + 30 + 0 + 90
+    assert_eq!(
+        actions_to_break(240.0, &[vec![30.0, 90.0], vec![0.0]]),
+        Some(7)
+    );
+    // a sliver per action gives up instead of looping for ever
+    assert_eq!(actions_to_break(240.0, &[vec![1e-9]]), None);
+    assert_eq!(
+        actions_to_break(240.0, &[vec![240.0 / MAX_ACTIONS as f64 + 1e-9]]),
+        Some(MAX_ACTIONS)
+    );
+}
+
+#[tokio::test]
+async fn break_damage() {
+    let cocolia = enemy_at_level(2013010, 80).await.unwrap();
+    let quantum = element_break(Element::Quantum, &LEVEL_80, &cocolia);
+    assert!(quantum.weak);
+    // 0.5 element, 240 toughness, half the damage through DEF, unbroken
+    let expected = LEVEL_80_MULTIPLIER * 0.5 * (0.5 + 80.0 / 120.0) * 0.5 * 0.9;
+    assert!((quantum.break_damage - expected).abs() < 1e-6);
+    assert_eq!(quantum.effect.name, "Entanglement");
+    assert!((quantum.effect.action_delay - 0.2).abs() < 1e-9);
+
+    // not a weakness, so the 20% RES applies
+    let attacker = BreakAttacker {
+        break_effect: 1.0,
+        ..LEVEL_80
+    };
+    let ice = element_break(Element::Ice, &attacker, &cocolia);
+    assert!(!ice.weak);
+    let expected = LEVEL_80_MULTIPLIER * (0.5 + 80.0 / 120.0) * 2.0 * 0.5 * 0.8 * 0.9;
+    assert!((ice.break_damage - expected).abs() < 1e-6);
+    assert_eq!(ice.effect.turns, 1);
+
+    // bleed is capped by the HP of a weak enemy
+    let soldier = enemy_at_level(1012010, 1).await.unwrap();
+    let bleed = element_break(Element::Physical, &LEVEL_80, &soldier).effect;
+    assert!((bleed.damage_per_turn - 60.0 * 0.16 * 100.0 / 121.0 * 0.8).abs() < 1e-6);
+
+    let imaginary = element_break(Element::Imaginary, &LEVEL_80, &soldier).effect;
+    assert_eq!(imaginary.damage_per_turn, 0.0);
+    assert_eq!(imaginary.speed_reduction, 0.1);
+}
+
+#[tokio::test]
+async fn team() {
+    let member = |avatar_id| TeamBreakMember {
+        avatar_id,
+        level: 80,
+        level_multiplier: None,
+        break_effect: 0.0,
+        weakness_break_efficiency: 0.0,
+        rotation: vec![SkillType::BPSkill],
+    };
+    let request = TeamBreakRequest {
+        monster_id: 2013010,
+        enemy_level: 80,
+        team: vec![member(1102), member(1001)],
+    };
+    // Seele's skill takes 60 off the 240 of Cocolia, March 7th isn't Quantum
+    let Json(result) = team_break(Json(request.clone())).await.unwrap();
+    assert_eq!(result.members[0].actions, Some(4));
+    assert_eq!(result.members[1].actions, None);
+    assert_eq!(result.members[1].reductions[0].reduction, 0.0);
+    assert_eq!(result.team_actions, Some(7));
+    assert_eq!(result.breaker, Some(1102));
+
+    let mut request = request;
+    request.team[0].weakness_break_efficiency = 0.5;
+    request.team[0].rotation = vec![SkillType::Ultra];
+    let Json(result) = team_break(Json(request.clone())).await.unwrap();
+    assert_eq!(result.members[0].reductions[0].reduction, 135.0);
+    assert_eq!(result.team_actions, Some(3));
+
+    let mut adversarial = request.clone();
+    adversarial.team[0].weakness_break_efficiency = -0.999999999;
+    assert!(team_break(Json(adversarial)).await.is_err());
+    let mut adversarial = request.clone();
+    adversarial.team[0].break_effect = f64::NAN;
+    assert!(team_break(Json(adversarial)).await.is_err());
+
+    request.team[1].level = 90;
+    assert!(team_break(Json(request.clone())).await.is_err());
+    request.team[1].level_multiplier = Some(4000.0);
+    assert!(team_break(Json(request)).await.is_ok());
+}
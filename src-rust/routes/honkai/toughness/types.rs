@@ -0,0 +1,108 @@
+use crate::routes::honkai::dm_api::{
+    monster::types::Enemy,
+    types::{Element, SkillType},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// what the attacker brings to a break, independent of the element
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
+pub struct BreakAttacker {
+    pub level: u32,
+    /// base damage of the attacker level, see `level_multiplier`
+    pub level_multiplier: f64,
+    /// 0.5 for 50%
+    pub break_effect: f64,
+}
+
+/// the break of one element against one enemy
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ElementBreak {
+    pub element: Element,
+    /// only weaknesses can be broken, the damage is what it would be if they
+    /// could
+    pub weak: bool,
+    pub break_damage: f64,
+    pub effect: BreakEffect,
+}
+
+/// the debuff a break of an element leaves on the enemy
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct BreakEffect {
+    pub name: String,
+    pub turns: u32,
+    /// 0 for effects that deal no damage
+    pub damage_per_turn: f64,
+    /// share of a full turn the enemy is pushed back by
+    pub action_delay: f64,
+    /// 0.1 for 10% less SPD
+    pub speed_reduction: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TeamBreakRequest {
+    pub monster_id: u32,
+    #[serde(default = "default_enemy_level")]
+    pub enemy_level: u32,
+    pub team: Vec<TeamBreakMember>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TeamBreakMember {
+    pub avatar_id: u32,
+    #[serde(default = "default_level")]
+    pub level: u32,
+    /// needed for levels without a known multiplier
+    #[serde(default)]
+    pub level_multiplier: Option<f64>,
+    #[serde(default)]
+    pub break_effect: f64,
+    /// 0.5 for 50% more toughness reduction
+    #[serde(default)]
+    pub weakness_break_efficiency: f64,
+    /// actions on repeat, only the main target of each counts
+    #[serde(default = "default_rotation")]
+    pub rotation: Vec<SkillType>,
+}
+
+fn default_enemy_level() -> u32 {
+    1
+}
+
+fn default_level() -> u32 {
+    80
+}
+
+fn default_rotation() -> Vec<SkillType> {
+    vec![SkillType::BPSkill]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TeamBreak {
+    pub enemy: Enemy,
+    pub members: Vec<MemberBreak>,
+    /// actions the team needs taking turns in team order, `None` when it
+    /// can't break the enemy at all
+    pub team_actions: Option<u32>,
+    /// avatar that lands the break in that order
+    pub breaker: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MemberBreak {
+    pub avatar_id: u32,
+    pub name: String,
+    pub element: Element,
+    /// toughness reduction of every action of the rotation, in the unit of
+    /// the enemy toughness
+    pub reductions: Vec<ActionReduction>,
+    /// actions needed alone, `None` when the member can't break the enemy
+    pub actions: Option<u32>,
+    pub damage: ElementBreak,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
+pub struct ActionReduction {
+    pub action: SkillType,
+    pub reduction: f64,
+}
@@ -1,18 +1,130 @@
-use crate::handler::error::WorkerError;
+use crate::{builder::config::env_config, handler::error::WorkerError};
 use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
-use std::path::{Path, PathBuf};
-use tracing::info;
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+use tracing::{info, warn};
 use url::Url;
 
-#[cfg(target_os = "windows")]
-const PREFIX_LOCAL_TMP: &str = "c:\\tmp\\";
-#[cfg(target_os = "linux")]
-const PREFIX_LOCAL_TMP: &str = "/tmp/";
+/// dataset directory readers are switched to once a snapshot is promoted,
+/// `None` falls back to the configured `local_repo`
+static ACTIVE_DATASET_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+const SPLITTED_DIR: &str = ".splitted";
 
-const PREFIX_LOCAL_REPO: &str = "../StarRailData";
+tokio::task_local! {
+    /// overrides the dataset directory for the current task, used to read a
+    /// staged snapshot before it is promoted. Reads under it never fall back
+    /// to the tmp dir or the remote
+    pub static DATASET_ROOT_OVERRIDE: PathBuf;
+}
+
+/// directory `DbData::to_repo` resolves against
+pub fn dataset_root() -> PathBuf {
+    if let Ok(root) = DATASET_ROOT_OVERRIDE.try_with(|root| root.clone()) {
+        return root;
+    }
+    let active = match ACTIVE_DATASET_ROOT.read() {
+        Ok(active) => active.clone(),
+        // a writer panicked mid swap, the old value is still a valid path
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    active.unwrap_or_else(|| env_config().data.local_repo.clone())
+}
+
+/// atomically switches every reader to a new dataset directory
+pub fn set_dataset_root(root: PathBuf) {
+    let mut active = match ACTIVE_DATASET_ROOT.write() {
+        Ok(active) => active,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *active = Some(root);
+}
+
+/// per-id split files of the current dataset, written next to it so every
+/// snapshot keeps its own
+pub fn splitted_dir() -> PathBuf {
+    dataset_root().join(SPLITTED_DIR)
+}
+
+/// a record of `table` that failed to convert is logged and left out, so
+/// one broken record doesn't take the whole table down
+pub fn skip_bad_record<T>(table: &str, record: Result<T, WorkerError>) -> Option<T> {
+    match record {
+        Ok(record) => Some(record),
+        Err(err) => {
+            warn!("skipping a record of {table}: {err}");
+            None
+        }
+    }
+}
+
+/// a value built from the dataset, rebuilt once readers are on another
+/// dataset directory
+pub struct DatasetCache<T> {
+    cached: RwLock<Option<(PathBuf, Arc<T>)>>,
+    /// bumped by `clear`, so a build that started before is not kept
+    clears: AtomicU64,
+}
+
+impl<T> DatasetCache<T> {
+    pub const fn new() -> Self {
+        Self {
+            cached: RwLock::new(None),
+            clears: AtomicU64::new(0),
+        }
+    }
+
+    /// the cached value if it was built from the current `dataset_root`,
+    /// else the value of `init`. Concurrent misses may each build it once
+    pub async fn get_or_try_init<F, Fut>(&self, init: F) -> Result<Arc<T>, WorkerError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, WorkerError>>,
+    {
+        let root = dataset_root();
+        if let Some(value) = self.get(&root) {
+            return Ok(value);
+        }
+        let clears = self.clears.load(Ordering::SeqCst);
+        let value = Arc::new(init().await?);
+        let mut cached = self.write();
+        if self.clears.load(Ordering::SeqCst) == clears {
+            *cached = Some((root, value.clone()));
+        }
+        Ok(value)
+    }
+
+    /// the next read builds the value again
+    pub fn clear(&self) {
+        let mut cached = self.write();
+        *cached = None;
+        self.clears.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn get(&self, root: &Path) -> Option<Arc<T>> {
+        let cached = match self.cached.read() {
+            Ok(cached) => cached,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match cached.as_ref() {
+            Some((built_from, value)) if built_from == root => Some(value.clone()),
+            _ => None,
+        }
+    }
 
-const PREFIX_REMOTE: &str = "https://raw.githubusercontent.com/Dimbreath/StarRailData/master/";
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Option<(PathBuf, Arc<T>)>> {
+        match self.cached.write() {
+            Ok(cached) => cached,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
 
 #[async_trait]
 pub trait DbData // <T>
@@ -30,23 +142,24 @@ pub trait DbData // <T>
     /// # Errors
     ///
     /// This function will return an error if fetching data from fallback_url
-    /// or writing to disk failed.
+    /// or writing to disk failed, or right away in offline mode.
     async fn try_write_disk() -> Result<String, WorkerError> {
-        let path_suffix = Self::path_data();
-
+        if env_config().data.offline {
+            return Err(WorkerError::OfflineCacheMiss(Self::path_data().to_owned()));
+        }
         let data = reqwest::get(Self::to_url()?).await?.text().await?;
-        std::fs::write(Path::new(PREFIX_LOCAL_TMP).join(path_suffix), data.clone())?;
+        std::fs::write(Self::to_local_tmp(), data.clone())?;
         Ok(data)
     }
 
     fn to_url() -> Result<Url, WorkerError> {
-        Ok(Url::parse(PREFIX_REMOTE)?.join(Self::path_data())?)
+        Ok(Url::parse(&env_config().data.remote)?.join(Self::path_data())?)
     }
     fn to_local_tmp() -> PathBuf {
-        Path::new(PREFIX_LOCAL_TMP).join(Self::path_data())
+        env_config().data.local_tmp.join(Self::path_data())
     }
     fn to_repo() -> PathBuf {
-        Path::new(PREFIX_LOCAL_REPO).join(Self::path_data())
+        dataset_root().join(Self::path_data())
     }
 
     /// read the local file for data, lazily writes from fallback url if not
@@ -57,6 +170,12 @@ pub trait DbData // <T>
         let tmp_path = Self::to_local_tmp();
         let repo_path = Self::to_repo();
 
+        // a pinned snapshot is read as is, a fallback would hide the files
+        // missing from it
+        if DATASET_ROOT_OVERRIDE.try_with(|_| ()).is_ok() && !repo_path.exists() {
+            return Err(WorkerError::NotFound(repo_path.display().to_string()));
+        }
+
         // WARN: this results in runtime crashes if the upstream type is
         // different from normal types
         // (one example being any HashMap that has multiple depths that we
@@ -83,3 +202,80 @@ pub trait DbData // <T>
 
     async fn upstream_convert(from: Self::TUpstream) -> Result<Self::TLocal, WorkerError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DatasetCache, DbData, DATASET_ROOT_OVERRIDE};
+    use crate::handler::error::WorkerError;
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use std::{
+        path::PathBuf,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    struct Missing;
+
+    #[async_trait]
+    impl DbData for Missing {
+        type TUpstream = Value;
+        type TLocal = Value;
+
+        fn path_data() -> &'static str {
+            "ExcelOutput/Missing.json"
+        }
+
+        async fn upstream_convert(from: Value) -> Result<Value, WorkerError> {
+            Ok(from)
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_cache_miss() {
+        let err = Missing::read().await.unwrap_err();
+        assert!(
+            matches!(&err, WorkerError::OfflineCacheMiss(path) if path == Missing::path_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_snapshot_miss() {
+        let err = DATASET_ROOT_OVERRIDE
+            .scope(PathBuf::from("/tmp/empty-snapshot"), Missing::read())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, WorkerError::NotFound(path) if path.starts_with("/tmp/empty-snapshot"))
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_write_disk() {
+        // the fixture config is offline, nothing may be fetched
+        let err = Missing::try_write_disk().await.unwrap_err();
+        assert!(matches!(err, WorkerError::OfflineCacheMiss(_)));
+        assert!(!Missing::to_local_tmp().exists());
+    }
+
+    #[tokio::test]
+    async fn dataset_cache() {
+        let cache: DatasetCache<u32> = DatasetCache::new();
+        let builds = AtomicU32::new(0);
+        let build = || async { Ok(builds.fetch_add(1, Ordering::SeqCst)) };
+
+        let first = *cache.get_or_try_init(build).await.unwrap();
+        assert_eq!(*cache.get_or_try_init(build).await.unwrap(), first);
+
+        // another snapshot gets its own value
+        let other = DATASET_ROOT_OVERRIDE
+            .scope(PathBuf::from("/tmp/other-snapshot"), async {
+                *cache.get_or_try_init(build).await.unwrap()
+            })
+            .await;
+        assert_ne!(other, first);
+
+        cache.clear();
+        cache.get_or_try_init(build).await.unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,8 +1,10 @@
 use self::{
-    cron::write_db, dotfiles::dotfiles_routes, health::health_check, honkai::honkai_routes,
+    cron::{status, validate, write_db},
+    dotfiles::dotfiles_routes, health::health_check, honkai::honkai_routes,
     rpc_routes::rpc_routes, utils::utils_routes,
 };
-use axum::{routing::get, Router};
+use crate::handler::request_id::{make_span, request_id};
+use axum::{body::Body, middleware, routing::get, Router};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
 pub mod cron;
@@ -21,10 +23,15 @@ pub fn app_router() -> Router {
         .nest("/cron", cron_routes())
         .nest("/", rpc_routes())
         .route("/health", get(health_check).post(health_check))
-        .layer(TraceLayer::new_for_http())
+        .layer(TraceLayer::new_for_http().make_span_with(make_span::<Body>))
+        // outside of the trace layer so its span has the id
+        .layer(middleware::from_fn(request_id))
         .layer(CorsLayer::permissive())
 }
 
 fn cron_routes() -> Router {
-    Router::new().route("/write_db", get(write_db::execute))
+    Router::new()
+        .route("/write_db", get(write_db::execute))
+        .route("/validate", get(validate::execute))
+        .route("/status", get(status::execute))
 }
@@ -1,7 +1,15 @@
+use libsql_client::{Client, Statement, Value};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
 use tracing::info;
 
 use crate::{
-    builder::traits::DbAction,
+    builder::{
+        get_db_client_for,
+        migration::{ensure_schema, migrate_up},
+        traits::DbAction,
+    },
     handler::error::WorkerError,
     routes::honkai::dm_api::{
         character::{eidolon::*, types::*},
@@ -12,33 +20,397 @@ use crate::{
     },
 };
 
-pub async fn seed_common() -> Result<(), WorkerError> {
-    info!("seeding common tables...");
-    Path::seed().await?;
-    Element::seed().await?;
-    SkillType::seed().await?;
+/// every `DbAction` implementor, addressable by its type name. Variants are
+/// in seeding order, tables referenced by another entry come first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum DbTable {
+    Path,
+    Element,
+    SkillType,
+    ItemType,
+    ItemSubType,
+    ItemRarity,
+    Item,
+    AvatarConfig,
+    AvatarSkillConfig,
+    AvatarSkillTreeConfig,
+    AvatarRankConfig,
+    EquipmentSkillConfig,
+    EquipmentConfig,
+}
+
+impl DbTable {
+    pub fn tables(&self) -> &'static [&'static str] {
+        match self {
+            Self::Path => Path::tables(),
+            Self::Element => Element::tables(),
+            Self::SkillType => SkillType::tables(),
+            Self::ItemType => ItemType::tables(),
+            Self::ItemSubType => ItemSubType::tables(),
+            Self::ItemRarity => ItemRarity::tables(),
+            Self::Item => Item::tables(),
+            Self::AvatarConfig => AvatarConfig::tables(),
+            Self::AvatarSkillConfig => AvatarSkillConfig::tables(),
+            Self::AvatarSkillTreeConfig => AvatarSkillTreeConfig::tables(),
+            Self::AvatarRankConfig => AvatarRankConfig::tables(),
+            Self::EquipmentSkillConfig => EquipmentSkillConfig::tables(),
+            Self::EquipmentConfig => EquipmentConfig::tables(),
+        }
+    }
+
+    pub async fn seed_statements(&self) -> Result<Vec<Statement>, WorkerError> {
+        match self {
+            Self::Path => Path::seed_statements().await,
+            Self::Element => Element::seed_statements().await,
+            Self::SkillType => SkillType::seed_statements().await,
+            Self::ItemType => ItemType::seed_statements().await,
+            Self::ItemSubType => ItemSubType::seed_statements().await,
+            Self::ItemRarity => ItemRarity::seed_statements().await,
+            Self::Item => Item::seed_statements().await,
+            Self::AvatarConfig => AvatarConfig::seed_statements().await,
+            Self::AvatarSkillConfig => AvatarSkillConfig::seed_statements().await,
+            Self::AvatarSkillTreeConfig => AvatarSkillTreeConfig::seed_statements().await,
+            Self::AvatarRankConfig => AvatarRankConfig::seed_statements().await,
+            Self::EquipmentSkillConfig => EquipmentSkillConfig::seed_statements().await,
+            Self::EquipmentConfig => EquipmentConfig::seed_statements().await,
+        }
+    }
+
+    pub fn teardown_statements(&self) -> Vec<Statement> {
+        match self {
+            Self::Path => Path::teardown_statements(),
+            Self::Element => Element::teardown_statements(),
+            Self::SkillType => SkillType::teardown_statements(),
+            Self::ItemType => ItemType::teardown_statements(),
+            Self::ItemSubType => ItemSubType::teardown_statements(),
+            Self::ItemRarity => ItemRarity::teardown_statements(),
+            Self::Item => Item::teardown_statements(),
+            Self::AvatarConfig => AvatarConfig::teardown_statements(),
+            Self::AvatarSkillConfig => AvatarSkillConfig::teardown_statements(),
+            Self::AvatarSkillTreeConfig => AvatarSkillTreeConfig::teardown_statements(),
+            Self::AvatarRankConfig => AvatarRankConfig::teardown_statements(),
+            Self::EquipmentSkillConfig => EquipmentSkillConfig::teardown_statements(),
+            Self::EquipmentConfig => EquipmentConfig::teardown_statements(),
+        }
+    }
+
+    pub async fn seed(&self, client: &Client) -> Result<(), WorkerError> {
+        info!("seeding {self}...");
+        client.batch(self.seed_statements().await?).await?;
+        Ok(())
+    }
+
+    pub async fn teardown(&self, client: &Client) -> Result<(), WorkerError> {
+        info!("tearing down {self}...");
+        client.batch(self.teardown_statements()).await?;
+        Ok(())
+    }
+}
+
+/// rows changed by a seed in a single table
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SeedReport {
+    pub table: &'static str,
+    pub inserted: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+}
+
+/// statements that bring the database in line with the dataset
+#[derive(Debug, Default)]
+pub struct SeedPlan {
+    pub reports: Vec<SeedReport>,
+    /// inserts and updates, parent tables first
+    pub upserts: Vec<Statement>,
+    /// deletes, child tables first
+    pub deletes: Vec<Statement>,
+}
+
+impl SeedPlan {
+    /// every statement in the order `apply` runs them
+    pub fn statements(&self) -> impl Iterator<Item = &Statement> {
+        self.upserts.iter().chain(self.deletes.iter())
+    }
+
+    /// runs the plan in transactions of at most `SEED_CHUNK_SIZE`
+    /// statements. Upserts finish before any delete starts so a failed chunk
+    /// never leaves a child row pointing at a deleted or missing parent
+    pub async fn apply(&self, client: &Client) -> Result<(), WorkerError> {
+        let total = self.upserts.len() + self.deletes.len();
+        let mut done = 0;
+        for statements in [&self.upserts, &self.deletes] {
+            for chunk in statements.chunks(SEED_CHUNK_SIZE) {
+                client.batch(chunk.to_vec()).await?;
+                done += chunk.len();
+                info!("applied {done}/{total} statements");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// statements sent in a single transaction
+pub const SEED_CHUNK_SIZE: usize = 500;
 
-    ItemType::seed().await?;
-    ItemSubType::seed().await?;
-    ItemRarity::seed().await?;
+/// diffs `tables` against the database and applies only the changed rows
+pub async fn seed(client: &Client, tables: &[DbTable]) -> Result<Vec<SeedReport>, WorkerError> {
+    let plan = plan(client, tables).await?;
+    plan.apply(client).await?;
+    Ok(plan.reports)
+}
 
-    info!("common tables seeded!");
-    Ok(())
+/// row count of every table
+pub async fn row_counts(client: &Client) -> Result<Vec<(&'static str, usize)>, WorkerError> {
+    let mut counts = Vec::new();
+    for table in DbTable::iter().flat_map(|table| table.tables().iter()) {
+        let result = client
+            .execute(format!("SELECT COUNT(*) FROM {table}"))
+            .await?;
+        let count = match result.rows.first().and_then(|row| row.values.first()) {
+            Some(Value::Integer { value }) => *value as usize,
+            _ => 0,
+        };
+        counts.push((*table, count));
+    }
+    Ok(counts)
 }
 
-pub async fn seed_table() -> Result<(), WorkerError> {
-    info!("seeding main tables...");
+/// compares the rows `tables` would seed with the rows in the database by
+/// primary key. The dataset is seeded into a scratch SQLite file first so
+/// both sides are compared after the same conversion
+pub async fn plan(client: &Client, tables: &[DbTable]) -> Result<SeedPlan, WorkerError> {
+    ensure_schema(client).await?;
+    let scratch_path = std::env::temp_dir().join(format!(
+        "nas-ws-scratch-{:016x}.sqlite",
+        rand::random::<u64>()
+    ));
+    let scratch = get_db_client_for(&format!("sqlite:{}", scratch_path.display())).await?;
+    let result: Result<SeedPlan, WorkerError> = async {
+        migrate_up(&scratch, None).await?;
+        let mut plan = SeedPlan::default();
+        let mut deletes: Vec<Vec<Statement>> = Vec::new();
+        for table in tables {
+            table.seed(&scratch).await?;
+            for name in table.tables() {
+                let (report, upserts, table_deletes) = diff_table(&scratch, client, name).await?;
+                plan.reports.push(report);
+                plan.upserts.extend(upserts);
+                deletes.push(table_deletes);
+            }
+        }
+        plan.deletes = deletes.into_iter().rev().flatten().collect();
+        Ok(plan)
+    }
+    .await;
+    drop(scratch);
+    let _ = std::fs::remove_file(&scratch_path);
+    result
+}
+
+/// rows of a table keyed by their primary key
+struct TableRows {
+    columns: Vec<String>,
+    primary_key: Vec<usize>,
+    rows: BTreeMap<String, Vec<Value>>,
+}
+
+async fn diff_table(
+    dataset: &Client,
+    db: &Client,
+    table: &'static str,
+) -> Result<(SeedReport, Vec<Statement>, Vec<Statement>), WorkerError> {
+    let primary_key = primary_key(dataset, table).await?;
+    let dataset = fetch_rows(dataset, table, &primary_key).await?;
+    let db = fetch_rows(db, table, &primary_key).await?;
+    let mut report = SeedReport {
+        table,
+        ..Default::default()
+    };
+    let mut upserts = Vec::new();
+    let mut deletes = Vec::new();
+
+    for (key, row) in dataset.rows.iter() {
+        match db.rows.get(key) {
+            None => {
+                report.inserted += 1;
+                upserts.push(insert_statement(table, &dataset.columns, row));
+            }
+            Some(existing) if format!("{existing:?}") != format!("{row:?}") => {
+                report.updated += 1;
+                upserts.push(update_statement(table, &dataset, row));
+            }
+            Some(_) => report.unchanged += 1,
+        }
+    }
+    for (key, row) in db.rows.iter() {
+        if !dataset.rows.contains_key(key) {
+            report.deleted += 1;
+            deletes.push(delete_statement(table, &db, row));
+        }
+    }
+    Ok((report, upserts, deletes))
+}
+
+/// positions of the primary key columns, every column if the table has no
+/// primary key
+async fn primary_key(client: &Client, table: &str) -> Result<Vec<usize>, WorkerError> {
+    let result = client
+        .execute(format!("PRAGMA table_info({table})"))
+        .await?;
+    // table_info rows are (cid, name, type, notnull, dflt_value, pk)
+    let mut key: Vec<(i64, usize)> = result
+        .rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| match row.values.get(5) {
+            Some(Value::Integer { value }) if *value > 0 => Some((*value, index)),
+            _ => None,
+        })
+        .collect();
+    key.sort();
+    match key.is_empty() {
+        true => Ok((0..result.rows.len()).collect()),
+        false => Ok(key.into_iter().map(|(_, index)| index).collect()),
+    }
+}
+
+async fn fetch_rows(
+    client: &Client,
+    table: &str,
+    primary_key: &[usize],
+) -> Result<TableRows, WorkerError> {
+    let result = client.execute(format!("SELECT * FROM {table}")).await?;
+    let rows = result
+        .rows
+        .into_iter()
+        .map(|row| {
+            let key: Vec<&Value> = primary_key
+                .iter()
+                .map(|index| &row.values[*index])
+                .collect();
+            (format!("{key:?}"), row.values)
+        })
+        .collect();
+    Ok(TableRows {
+        columns: result.columns,
+        primary_key: primary_key.to_vec(),
+        rows,
+    })
+}
+
+fn insert_statement(table: &str, columns: &[String], row: &[Value]) -> Statement {
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    Statement::with_args(
+        format!(
+            "INSERT INTO {table} ({}) VALUES ({placeholders})",
+            columns.join(", ")
+        ),
+        row,
+    )
+}
+
+fn update_statement(table: &str, rows: &TableRows, row: &[Value]) -> Statement {
+    let (assignments, mut args): (Vec<String>, Vec<Value>) = rows
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !rows.primary_key.contains(index))
+        .map(|(index, column)| (format!("{column} = ?"), row[index].clone()))
+        .unzip();
+    let (condition, key_args) = key_condition(rows, row);
+    args.extend(key_args);
+    Statement::with_args(
+        format!(
+            "UPDATE {table} SET {} WHERE {condition}",
+            assignments.join(", ")
+        ),
+        &args,
+    )
+}
+
+fn delete_statement(table: &str, rows: &TableRows, row: &[Value]) -> Statement {
+    let (condition, args) = key_condition(rows, row);
+    Statement::with_args(format!("DELETE FROM {table} WHERE {condition}"), &args)
+}
+
+/// `WHERE` clause matching the primary key of `row`
+fn key_condition(rows: &TableRows, row: &[Value]) -> (String, Vec<Value>) {
+    let (conditions, args): (Vec<String>, Vec<Value>) = rows
+        .primary_key
+        .iter()
+        .map(|index| (format!("{} = ?", rows.columns[*index]), row[*index].clone()))
+        .unzip();
+    (conditions.join(" AND "), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan, row_counts, seed, DbTable, SeedReport};
+    use crate::builder::{get_db_client_for, migration::migrate_up};
+    use std::str::FromStr;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn table_by_name() {
+        assert_eq!(
+            DbTable::from_str("AvatarConfig").unwrap(),
+            DbTable::AvatarConfig
+        );
+        assert_eq!(DbTable::from_str("item").unwrap(), DbTable::Item);
+        assert!(DbTable::from_str("honkai_avatar").is_err());
+    }
+
+    fn report<'a>(reports: &'a [SeedReport], table: &str) -> &'a SeedReport {
+        reports.iter().find(|report| report.table == table).unwrap()
+    }
+
+    #[tokio::test]
+    async fn seed_fixture() {
+        let path = std::env::temp_dir().join("nas-ws-seed.sqlite");
+        let _ = std::fs::remove_file(&path);
+        let client = get_db_client_for(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        migrate_up(&client, None).await.unwrap();
+        let tables: Vec<DbTable> = DbTable::iter().collect();
 
-    Item::seed().await?;
+        let reports = seed(&client, &tables).await.unwrap();
+        assert_eq!(report(&reports, "honkai_avatar").inserted, 3);
+        let counts = row_counts(&client).await.unwrap();
+        let avatars = counts
+            .iter()
+            .find(|(table, _)| *table == "honkai_avatar")
+            .unwrap();
+        assert_eq!(avatars.1, 3);
 
-    AvatarConfig::seed().await?;
-    AvatarSkillConfig::seed().await?;
-    AvatarSkillTreeConfig::seed().await?;
-    AvatarRankConfig::seed().await?;
+        // a freshly seeded database matches the dataset
+        let unchanged = plan(&client, &tables).await.unwrap();
+        assert_eq!(unchanged.statements().count(), 0);
 
-    EquipmentSkillConfig::seed().await?;
-    EquipmentConfig::seed().await?;
+        // drifted rows are updated, unknown rows deleted child table first
+        client
+            .batch([
+                "UPDATE honkai_avatar SET name = 'Old' WHERE id = 1001",
+                "INSERT INTO honkai_avatar (id, name, rarity, votag, element, path, spneed) \
+                 VALUES (9999, 'Gone', 5, 'gone', 'Ice', 'Hunt', 100)",
+                "INSERT INTO honkai_avatarSkill (avatar_id, skill_id) VALUES (9999, 100101)",
+            ])
+            .await
+            .unwrap();
+        let drift = plan(&client, &tables).await.unwrap();
+        let avatar = report(&drift.reports, "honkai_avatar");
+        assert_eq!((avatar.updated, avatar.deleted, avatar.inserted), (1, 1, 0));
+        assert_eq!(report(&drift.reports, "honkai_avatarSkill").deleted, 1);
+        let deletes: Vec<String> = drift.deletes.iter().map(|st| st.to_string()).collect();
+        assert!(deletes[0].contains("honkai_avatarSkill"));
+        assert!(deletes[1].contains("honkai_avatar "));
 
-    info!("main tables seeded!");
-    Ok(())
+        drift.apply(&client).await.unwrap();
+        assert_eq!(
+            plan(&client, &tables).await.unwrap().statements().count(),
+            0
+        );
+    }
 }